@@ -0,0 +1,933 @@
+//! `spl-swaps`, a command-line tool for operating the atomic swaps of the `solana-spl-swaps` program.
+//! The cluster and keypair are read from the Solana CLI configuration, unless overridden.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::associated_token::spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use anchor_spl::token_interface::Mint;
+use anyhow::{anyhow, ensure, Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_config::{RpcProgramAccountsConfig, RpcTransactionConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::account::from_account;
+use solana_sdk::bs58;
+use solana_sdk::clock::Clock;
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signature, Signer};
+use solana_sdk::sysvar;
+use solana_sdk::transaction::Transaction;
+use solana_spl_swaps_client::events::{self, SwapEvent};
+use solana_spl_swaps_client::{
+    self as client, HashAlgorithm, InitiateArgs, Swap, SwapAccount, SwapKey, TimelockKind,
+    NATIVE_MINT, PROGRAM_ID,
+};
+use solana_transaction_status_client_types::option_serializer::OptionSerializer;
+use solana_transaction_status_client_types::{UiInstruction, UiTransactionEncoding};
+
+/// The number of decimals of native SOL
+const NATIVE_DECIMALS: u8 = 9;
+
+/// The offset of `mint` in the data of a `swap_data` PDA, following its discriminator
+/// and the `bump`, `expiry`, `timelock_kind`, `identity_pda_bump`, `token_vault`, `vault_isolated`
+/// and `rent_sponsor` fields
+const SWAP_MINT_OFFSET: usize = 8 + 1 + 8 + 1 + 1 + 32 + 1 + 32;
+
+/// The number of most recent transactions of a closed swap searched for the event completing it
+const COMPLETION_LOOKBACK: usize = 10;
+
+#[derive(Parser)]
+#[command(
+    name = "spl-swaps",
+    version,
+    about = "Operates atomic swaps of the solana-spl-swaps program"
+)]
+struct Cli {
+    /// The Solana CLI configuration file [default: ~/.config/solana/cli/config.yml]
+    #[arg(long, short = 'C', global = true)]
+    config: Option<PathBuf>,
+
+    /// The JSON RPC URL of the cluster, overriding that of the configuration
+    #[arg(long, short = 'u', global = true)]
+    url: Option<String>,
+
+    /// The keypair paying for and signing transactions, overriding that of the configuration
+    #[arg(long, short = 'k', global = true)]
+    keypair: Option<PathBuf>,
+
+    /// Prints the output as JSON
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Initiates a swap funded by the keypair, generating its secret unless a secret hash is given
+    Initiate {
+        /// The mint of the tokens to swap, or 11111111111111111111111111111111 for native SOL
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        redeemer: Pubkey,
+        /// Defaults to the keypair
+        #[arg(long)]
+        refundee: Option<Pubkey>,
+        /// The quantity of tokens to swap, in units of the mint (e.g 1.5)
+        #[arg(long)]
+        amount: String,
+        /// The number of slots or seconds, or the unix timestamp, as per `--timelock-kind`
+        #[arg(long)]
+        timelock: u64,
+        #[arg(long, value_enum, default_value_t = TimelockKindArg::Slots)]
+        timelock_kind: TimelockKindArg,
+        #[arg(long, value_enum, default_value_t = HashAlgorithmArg::Sha256)]
+        hash_algorithm: HashAlgorithmArg,
+        /// The hex-encoded secret hash, if the secret is known by the counterparty only
+        #[arg(long)]
+        secret_hash: Option<String>,
+        /// The maximum length in bytes of the secret accepted upon redeem
+        #[arg(long, default_value_t = 32)]
+        max_secret_len: u16,
+        /// Defaults to a random value
+        #[arg(long)]
+        salt: Option<u64>,
+        /// The quantity of tokens paid to the relayer of the redeem or refund, in units of the mint
+        #[arg(long, default_value = "0")]
+        relayer_tip: String,
+    },
+    /// Redeems a swap to the current redeemer with the hex-encoded secret
+    Redeem {
+        swap: Pubkey,
+        #[arg(long)]
+        secret: String,
+    },
+    /// Refunds an expired swap to the current refundee
+    Refund { swap: Pubkey },
+    /// Refunds a swap to the current refundee before its expiry. The keypair must be the current redeemer.
+    InstantRefund { swap: Pubkey },
+    /// Prints the state of a swap
+    Status { swap: Pubkey },
+    /// Lists the open swaps
+    List {
+        #[arg(long)]
+        mint: Option<Pubkey>,
+        /// Only lists the swaps of which this key is the redeemer or the refundee
+        #[arg(long)]
+        party: Option<Pubkey>,
+    },
+    /// Prints the swaps as they are opened and closed
+    Watch {
+        #[arg(long)]
+        mint: Option<Pubkey>,
+        /// The polling interval in seconds
+        #[arg(long, default_value_t = 5)]
+        interval: u64,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TimelockKindArg {
+    Slots,
+    Seconds,
+    Timestamp,
+}
+
+impl From<TimelockKindArg> for TimelockKind {
+    fn from(kind: TimelockKindArg) -> Self {
+        match kind {
+            TimelockKindArg::Slots => TimelockKind::Slots,
+            TimelockKindArg::Seconds => TimelockKind::Seconds,
+            TimelockKindArg::Timestamp => TimelockKind::Timestamp,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum HashAlgorithmArg {
+    Sha256,
+    Keccak256,
+    Hash160,
+}
+
+impl From<HashAlgorithmArg> for HashAlgorithm {
+    fn from(algorithm: HashAlgorithmArg) -> Self {
+        match algorithm {
+            HashAlgorithmArg::Sha256 => HashAlgorithm::Sha256,
+            HashAlgorithmArg::Keccak256 => HashAlgorithm::Keccak256,
+            HashAlgorithmArg::Hash160 => HashAlgorithm::Hash160,
+        }
+    }
+}
+
+/// The fields of the Solana CLI configuration file used by this tool
+#[derive(Deserialize)]
+#[serde(default)]
+struct SolanaConfig {
+    json_rpc_url: String,
+    keypair_path: String,
+    commitment: String,
+}
+
+impl Default for SolanaConfig {
+    /// The defaults of the Solana CLI
+    fn default() -> Self {
+        Self {
+            json_rpc_url: "https://api.mainnet-beta.solana.com".to_string(),
+            keypair_path: home_path(".config/solana/id.json"),
+            commitment: "confirmed".to_string(),
+        }
+    }
+}
+
+fn home_path(path: &str) -> String {
+    let home = std::env::var("HOME").unwrap_or_default();
+    format!("{home}/{path}")
+}
+
+/// The cluster connection and the keypair of the operator
+struct Operator {
+    rpc: RpcClient,
+    keypair_path: String,
+}
+
+impl Operator {
+    fn new(cli: &Cli) -> Result<Self> {
+        let config_path = match &cli.config {
+            Some(path) => path.clone(),
+            None => PathBuf::from(home_path(".config/solana/cli/config.yml")),
+        };
+        let config: SolanaConfig = match std::fs::read_to_string(&config_path) {
+            Ok(contents) => serde_yaml::from_str(&contents)
+                .with_context(|| format!("invalid configuration {}", config_path.display()))?,
+            Err(_) if cli.config.is_none() => SolanaConfig::default(),
+            Err(err) => return Err(err).context(format!("reading {}", config_path.display())),
+        };
+        let commitment = config
+            .commitment
+            .parse::<CommitmentLevel>()
+            .map_err(|err| anyhow!("invalid commitment {}: {err}", config.commitment))?;
+        let url = cli.url.clone().unwrap_or(config.json_rpc_url);
+        let keypair_path = match &cli.keypair {
+            Some(path) => path.display().to_string(),
+            None => config.keypair_path,
+        };
+
+        Ok(Self {
+            rpc: RpcClient::new_with_commitment(url, CommitmentConfig { commitment }),
+            keypair_path,
+        })
+    }
+
+    fn keypair(&self) -> Result<Keypair> {
+        read_keypair_file(&self.keypair_path)
+            .map_err(|err| anyhow!("reading keypair {}: {err}", self.keypair_path))
+    }
+
+    /// Signs the instructions with `keypair`, also the fee payer, and sends them
+    fn send(&self, keypair: &Keypair, instructions: &[Instruction]) -> Result<String> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&keypair.pubkey()),
+            &[keypair],
+            blockhash,
+        );
+        let signature = self.rpc.send_and_confirm_transaction(&transaction)?;
+        Ok(signature.to_string())
+    }
+
+    fn swap(&self, address: &Pubkey) -> Result<Swap> {
+        let account = self
+            .rpc
+            .get_account(address)
+            .with_context(|| format!("swap {address} not found, it may have been completed"))?;
+        Ok(Swap::decode(*address, &account.data)?)
+    }
+
+    /// The open swaps, optionally of `mint` only
+    fn swaps(&self, mint: Option<Pubkey>) -> Result<Vec<Swap>> {
+        let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            0,
+            SwapAccount::DISCRIMINATOR.to_vec(),
+        ))];
+        if let Some(mint) = mint {
+            filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                SWAP_MINT_OFFSET,
+                mint.to_bytes().to_vec(),
+            )));
+        }
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            ..Default::default()
+        };
+        let mut swaps = self
+            .rpc
+            .get_program_accounts_with_config(&PROGRAM_ID, config)?
+            .into_iter()
+            .filter_map(|(address, account)| Swap::decode(address, &account.data).ok())
+            .collect::<Vec<_>>();
+        swaps.sort_by_key(|swap| swap.address);
+        Ok(swaps)
+    }
+
+    /// The lifecycle events emitted by the program in the transaction `signature`,
+    /// which are none if the transaction failed
+    fn events(&self, signature: &Signature) -> Result<Vec<SwapEvent>> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(self.rpc.commitment()),
+            max_supported_transaction_version: Some(0),
+        };
+        let transaction = self
+            .rpc
+            .get_transaction_with_config(signature, config)?
+            .transaction;
+        let meta = transaction
+            .meta
+            .with_context(|| format!("transaction {signature} has no status"))?;
+        let decoded = transaction
+            .transaction
+            .decode()
+            .with_context(|| format!("invalid transaction {signature}"))?;
+
+        let mut account_keys = decoded.message.static_account_keys().to_vec();
+        if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+            for address in loaded.writable.iter().chain(&loaded.readonly) {
+                account_keys.push(address.parse()?);
+            }
+        }
+        let mut instructions = Vec::new();
+        if let OptionSerializer::Some(inner_instructions) = &meta.inner_instructions {
+            for instruction in inner_instructions
+                .iter()
+                .flat_map(|inner| &inner.instructions)
+            {
+                let UiInstruction::Compiled(instruction) = instruction else {
+                    continue;
+                };
+                let program = account_keys
+                    .get(usize::from(instruction.program_id_index))
+                    .with_context(|| format!("invalid inner instruction in {signature}"))?;
+                instructions.push((*program, bs58::decode(&instruction.data).into_vec()?));
+            }
+        }
+        Ok(events::parse_inner_instructions(
+            instructions
+                .iter()
+                .map(|(program, data)| (program, data.as_slice())),
+            meta.err.is_none(),
+        )?)
+    }
+
+    /// The event completing the closed swap at `address`, along with the signature of its transaction,
+    /// searched for among the most recent successful transactions of the swap
+    fn completion(
+        &self,
+        address: &Pubkey,
+        order_id: [u8; 32],
+    ) -> Result<Option<(Signature, SwapEvent)>> {
+        let config = GetConfirmedSignaturesForAddress2Config {
+            limit: Some(COMPLETION_LOOKBACK),
+            ..Default::default()
+        };
+        for status in self
+            .rpc
+            .get_signatures_for_address_with_config(address, config)?
+        {
+            if status.err.is_some() {
+                continue;
+            }
+            let signature = status.signature.parse::<Signature>()?;
+            let completion = self.events(&signature)?.into_iter().find(|event| {
+                matches!(
+                    event,
+                    SwapEvent::Redeemed(_) | SwapEvent::Refunded(_) | SwapEvent::InstantRefunded(_)
+                ) && event.order_id() == order_id
+            });
+            if let Some(event) = completion {
+                return Ok(Some((signature, event)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The token program owning `mint` and the decimals of `mint`
+    fn mint(&self, mint: &Pubkey) -> Result<(Pubkey, u8)> {
+        if *mint == NATIVE_MINT {
+            return Ok((Pubkey::default(), NATIVE_DECIMALS));
+        }
+        let account = self
+            .rpc
+            .get_account(mint)
+            .with_context(|| format!("mint {mint} not found"))?;
+        let decimals = Mint::try_deserialize(&mut &account.data[..])?.decimals;
+        Ok((account.owner, decimals))
+    }
+
+    fn clock(&self) -> Result<Clock> {
+        let account = self.rpc.get_account(&sysvar::clock::ID)?;
+        from_account(&account).ok_or_else(|| anyhow!("invalid clock sysvar"))
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let operator = Operator::new(&cli)?;
+
+    match &cli.command {
+        Command::Initiate {
+            mint,
+            redeemer,
+            refundee,
+            amount,
+            timelock,
+            timelock_kind,
+            hash_algorithm,
+            secret_hash,
+            max_secret_len,
+            salt,
+            relayer_tip,
+        } => {
+            let keypair = operator.keypair()?;
+            let (token_program, decimals) = operator.mint(mint)?;
+            let hash_algorithm = HashAlgorithm::from(*hash_algorithm);
+
+            let (secret, secret_hash) = match secret_hash {
+                Some(secret_hash) => (None, parse_secret_hash(secret_hash, hash_algorithm)?),
+                None => {
+                    let secret: [u8; 32] = rand::random();
+                    (Some(secret), hash_algorithm.digest(&secret))
+                }
+            };
+            let key = SwapKey {
+                mint: *mint,
+                redeemer: *redeemer,
+                refundee: refundee.unwrap_or(keypair.pubkey()),
+                secret_hash,
+                swap_amount: parse_amount(amount, decimals)?,
+                timelock: *timelock,
+                salt: salt.unwrap_or_else(rand::random),
+            };
+            let args = InitiateArgs {
+                timelock_kind: (*timelock_kind).into(),
+                hash_algorithm,
+                max_secret_len: *max_secret_len,
+                relayer_tip: parse_amount(relayer_tip, decimals)?,
+                destination_data: None,
+            };
+            let instruction = if *mint == NATIVE_MINT {
+                client::initiate_native(&key, args, &keypair.pubkey(), &keypair.pubkey())
+            } else {
+                let funder_token_account = get_associated_token_address_with_program_id(
+                    &keypair.pubkey(),
+                    mint,
+                    &token_program,
+                );
+                client::initiate(
+                    &key,
+                    args,
+                    &keypair.pubkey(),
+                    &funder_token_account,
+                    &keypair.pubkey(),
+                    &token_program,
+                )
+            };
+            let signature = operator.send(&keypair, &[instruction])?;
+
+            print_output(
+                cli.json,
+                &json!({
+                    "swap": key.swap_data_pda().0.to_string(),
+                    "order_id": hex::encode(key.order_id()),
+                    "salt": key.salt,
+                    "secret": secret.map(hex::encode),
+                    "secret_hash": hex::encode(secret_hash),
+                    "signature": signature,
+                }),
+            );
+        }
+        Command::Redeem { swap, secret } => {
+            let keypair = operator.keypair()?;
+            let relayer = keypair.pubkey();
+            let swap = operator.swap(swap)?;
+            let account = &swap.account;
+            let secret = hex::decode(secret).context("the secret must be hex-encoded")?;
+            let instructions = if account.mint == NATIVE_MINT {
+                let fee_recipient = (account.fee_bps > 0).then_some(account.fee_recipient);
+                vec![swap.redeem_native(secret, fee_recipient, &relayer)]
+            } else {
+                let (token_program, _) = operator.mint(&account.mint)?;
+                let mut instructions = Vec::new();
+                let redeemer_token_account = associated_token_account(
+                    &relayer,
+                    &account.current_redeemer,
+                    account,
+                    &token_program,
+                    &mut instructions,
+                );
+                let fee_token_account = (account.fee_bps > 0).then(|| {
+                    associated_token_account(
+                        &relayer,
+                        &account.fee_recipient,
+                        account,
+                        &token_program,
+                        &mut instructions,
+                    )
+                });
+                let relayer_token_account = (account.relayer_tip > 0).then(|| {
+                    associated_token_account(
+                        &relayer,
+                        &relayer,
+                        account,
+                        &token_program,
+                        &mut instructions,
+                    )
+                });
+                instructions.push(swap.redeem(
+                    secret,
+                    &redeemer_token_account,
+                    fee_token_account,
+                    &relayer,
+                    relayer_token_account,
+                    &token_program,
+                ));
+                instructions
+            };
+            let signature = operator.send(&keypair, &instructions)?;
+
+            print_output(cli.json, &json!({ "signature": signature }));
+        }
+        Command::Refund { swap } => {
+            let keypair = operator.keypair()?;
+            let relayer = keypair.pubkey();
+            let swap = operator.swap(swap)?;
+            let account = &swap.account;
+            ensure!(
+                has_expired(account, &operator.clock()?),
+                "the swap expires at {} ({:?})",
+                account.expiry,
+                account.timelock_kind
+            );
+            let instructions = if account.mint == NATIVE_MINT {
+                vec![swap.refund_native(&relayer)]
+            } else {
+                let (token_program, _) = operator.mint(&account.mint)?;
+                let mut instructions = Vec::new();
+                let refundee_token_account = associated_token_account(
+                    &relayer,
+                    &account.current_refundee,
+                    account,
+                    &token_program,
+                    &mut instructions,
+                );
+                let relayer_token_account = (account.relayer_tip > 0).then(|| {
+                    associated_token_account(
+                        &relayer,
+                        &relayer,
+                        account,
+                        &token_program,
+                        &mut instructions,
+                    )
+                });
+                instructions.push(swap.refund(
+                    &refundee_token_account,
+                    &relayer,
+                    relayer_token_account,
+                    &token_program,
+                ));
+                instructions
+            };
+            let signature = operator.send(&keypair, &instructions)?;
+
+            print_output(cli.json, &json!({ "signature": signature }));
+        }
+        Command::InstantRefund { swap } => {
+            let keypair = operator.keypair()?;
+            let swap = operator.swap(swap)?;
+            let account = &swap.account;
+            ensure!(
+                keypair.pubkey() == account.current_redeemer,
+                "the keypair is not the current redeemer {}",
+                account.current_redeemer
+            );
+            let instructions = if account.mint == NATIVE_MINT {
+                vec![swap.instant_refund_native()]
+            } else {
+                let (token_program, _) = operator.mint(&account.mint)?;
+                let mut instructions = Vec::new();
+                let refundee_token_account = associated_token_account(
+                    &keypair.pubkey(),
+                    &account.current_refundee,
+                    account,
+                    &token_program,
+                    &mut instructions,
+                );
+                instructions.push(swap.instant_refund(&refundee_token_account, &token_program));
+                instructions
+            };
+            let signature = operator.send(&keypair, &instructions)?;
+
+            print_output(cli.json, &json!({ "signature": signature }));
+        }
+        Command::Status { swap } => {
+            let swap = operator.swap(swap)?;
+            let (_, decimals) = operator.mint(&swap.account.mint)?;
+            let mut status = swap_json(&swap, decimals);
+            status["refundable"] = has_expired(&swap.account, &operator.clock()?).into();
+
+            print_output(cli.json, &status);
+        }
+        Command::List { mint, party } => {
+            let mut decimals = BTreeMap::new();
+            let mut swaps = Vec::new();
+            for swap in operator.swaps(*mint)? {
+                let account = &swap.account;
+                if party.is_some_and(|party| {
+                    account.current_redeemer != party && account.current_refundee != party
+                }) {
+                    continue;
+                }
+                let decimals = match decimals.get(&account.mint) {
+                    Some(decimals) => *decimals,
+                    None => *decimals
+                        .entry(account.mint)
+                        .or_insert(operator.mint(&account.mint)?.1),
+                };
+                swaps.push(swap_json(&swap, decimals));
+            }
+
+            print_output(cli.json, &Value::Array(swaps));
+        }
+        Command::Watch { mint, interval } => {
+            // A failed poll is retried at the next interval, rather than ending the watch
+            let mut open: Option<BTreeMap<Pubkey, Swap>> = None;
+            loop {
+                match operator.swaps(*mint) {
+                    Ok(swaps) => {
+                        let current = swaps
+                            .into_iter()
+                            .map(|swap| (swap.address, swap))
+                            .collect::<BTreeMap<_, _>>();
+                        match &open {
+                            Some(open) => watch_changes(&operator, cli.json, open, &current),
+                            None => eprintln!("Watching {} open swaps", current.len()),
+                        }
+                        open = Some(current);
+                    }
+                    Err(err) => eprintln!("Failed to fetch the open swaps: {err:#}"),
+                }
+                thread::sleep(Duration::from_secs(*interval));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the swaps opened, updated and closed between the `open` and `current` snapshots.
+/// Closed swaps are reported along with the event completing them, such as the secret of a redeem.
+fn watch_changes(
+    operator: &Operator,
+    json: bool,
+    open: &BTreeMap<Pubkey, Swap>,
+    current: &BTreeMap<Pubkey, Swap>,
+) {
+    for (address, swap) in current {
+        let Some(previous) = open.get(address) else {
+            let mut opened = match operator.mint(&swap.account.mint) {
+                Ok((_, decimals)) => swap_json(swap, decimals),
+                Err(err) => {
+                    eprintln!("Failed to fetch the mint of swap {address}: {err:#}");
+                    json!({ "swap": address.to_string() })
+                }
+            };
+            opened["event"] = "opened".into();
+            print_output(json, &opened);
+            continue;
+        };
+        if previous.account.current_refundee != swap.account.current_refundee {
+            print_output(
+                json,
+                &json!({
+                    "event": "refundee_updated",
+                    "swap": address.to_string(),
+                    "order_id": hex::encode(swap.account.order_id),
+                    "previous_refundee": previous.account.current_refundee.to_string(),
+                    "new_refundee": swap.account.current_refundee.to_string(),
+                }),
+            );
+        }
+        if previous.account.current_redeemer != swap.account.current_redeemer {
+            print_output(
+                json,
+                &json!({
+                    "event": "redeemer_transferred",
+                    "swap": address.to_string(),
+                    "order_id": hex::encode(swap.account.order_id),
+                    "previous_redeemer": previous.account.current_redeemer.to_string(),
+                    "new_redeemer": swap.account.current_redeemer.to_string(),
+                }),
+            );
+        }
+    }
+    for (address, swap) in open {
+        if current.contains_key(address) {
+            continue;
+        }
+        let order_id = swap.account.order_id;
+        let mut closed = json!({
+            "event": "closed",
+            "swap": address.to_string(),
+            "order_id": hex::encode(order_id),
+        });
+        match operator.completion(address, order_id) {
+            Ok(Some((signature, event))) => {
+                closed["signature"] = signature.to_string().into();
+                match event {
+                    SwapEvent::Redeemed(event) => {
+                        closed["event"] = "redeemed".into();
+                        closed["secret"] = hex::encode(event.secret).into();
+                        closed["destination"] = event.destination.to_string().into();
+                    }
+                    SwapEvent::Refunded(event) => {
+                        closed["event"] = "refunded".into();
+                        closed["destination"] = event.destination.to_string().into();
+                    }
+                    SwapEvent::InstantRefunded(event) => {
+                        closed["event"] = "instant_refunded".into();
+                        closed["destination"] = event.destination.to_string().into();
+                    }
+                    SwapEvent::Initiated(_)
+                    | SwapEvent::RefundeeUpdated(_)
+                    | SwapEvent::RedeemerTransferred(_) => {}
+                }
+            }
+            Ok(None) => eprintln!("No event completing swap {address} was found"),
+            Err(err) => eprintln!("Failed to fetch the completion of swap {address}: {err:#}"),
+        }
+        print_output(json, &closed);
+    }
+}
+
+/// The associated token account of `owner` for the mint of the swap, created by `payer` if it does not exist
+fn associated_token_account(
+    payer: &Pubkey,
+    owner: &Pubkey,
+    account: &SwapAccount,
+    token_program: &Pubkey,
+    instructions: &mut Vec<Instruction>,
+) -> Pubkey {
+    instructions.push(create_associated_token_account_idempotent(
+        payer,
+        owner,
+        &account.mint,
+        token_program,
+    ));
+    get_associated_token_address_with_program_id(owner, &account.mint, token_program)
+}
+
+/// Whether the expiry of the swap has passed, beyond which (non-instant) refunds are allowed
+fn has_expired(account: &SwapAccount, clock: &Clock) -> bool {
+    let now = match account.timelock_kind {
+        TimelockKind::Slots => clock.slot,
+        TimelockKind::Seconds | TimelockKind::Timestamp => clock.unix_timestamp.max(0) as u64,
+    };
+    now > account.expiry
+}
+
+fn swap_json(swap: &Swap, decimals: u8) -> Value {
+    let account = &swap.account;
+    json!({
+        "swap": swap.address.to_string(),
+        "order_id": hex::encode(account.order_id),
+        "mint": account.mint.to_string(),
+        "redeemer": account.current_redeemer.to_string(),
+        "refundee": account.current_refundee.to_string(),
+        "secret_hash": hex::encode(account.secret_hash),
+        "hash_algorithm": format!("{:?}", account.hash_algorithm),
+        "swap_amount": format_amount(account.swap_amount, decimals),
+        "escrowed_amount": format_amount(account.escrowed_amount, decimals),
+        "relayer_tip": format_amount(account.relayer_tip, decimals),
+        "fee_bps": account.fee_bps,
+        "timelock_kind": format!("{:?}", account.timelock_kind),
+        "expiry": account.expiry,
+        "vault_isolated": account.vault_isolated,
+    })
+}
+
+/// Prints `value` as JSON, or as `key: value` lines for humans
+fn print_output(json: bool, value: &Value) {
+    if json {
+        println!("{value}");
+        return;
+    }
+    match value {
+        Value::Array(values) => {
+            for value in values {
+                print_output(json, value);
+                println!();
+            }
+        }
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                match value {
+                    Value::Null => {}
+                    Value::String(value) => println!("{key}: {value}"),
+                    value => println!("{key}: {value}"),
+                }
+            }
+        }
+        value => println!("{value}"),
+    }
+}
+
+/// Parses a quantity in units of the mint (e.g 1.5) into base units
+fn parse_amount(amount: &str, decimals: u8) -> Result<u64> {
+    let decimals = usize::from(decimals);
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    ensure!(
+        fraction.len() <= decimals,
+        "{amount} has more than {decimals} decimals"
+    );
+    let digits = format!("{whole}{fraction:0<decimals$}");
+    digits
+        .parse::<u64>()
+        .with_context(|| format!("invalid amount {amount}"))
+}
+
+/// Formats a quantity in base units into units of the mint
+fn format_amount(amount: u64, decimals: u8) -> String {
+    let decimals = usize::from(decimals);
+    let digits = format!("{amount:0>width$}", width = decimals + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{whole}.{fraction}")
+    }
+}
+
+/// Parses a hex-encoded digest of `hash_algorithm` into a secret hash,
+/// zero-padding digests shorter than 32 bytes (i.e HASH160)
+fn parse_secret_hash(secret_hash: &str, hash_algorithm: HashAlgorithm) -> Result<[u8; 32]> {
+    let bytes = hex::decode(secret_hash).context("the secret hash must be hex-encoded")?;
+    let digest_len = hash_algorithm.digest_len();
+    ensure!(
+        bytes.len() == digest_len,
+        "a {hash_algorithm:?} secret hash must be {digest_len} bytes, not {}",
+        bytes.len()
+    );
+    let mut padded = [0; 32];
+    padded[..digest_len].copy_from_slice(&bytes);
+    Ok(padded)
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::AccountSerialize;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("1.5", 6).unwrap(), 1_500_000);
+        assert_eq!(parse_amount("1", 6).unwrap(), 1_000_000);
+        assert_eq!(parse_amount("0.000001", 6).unwrap(), 1);
+        assert_eq!(parse_amount(".5", 1).unwrap(), 5);
+        assert_eq!(parse_amount("42", 0).unwrap(), 42);
+        assert_eq!(parse_amount("18446744073709551615", 0).unwrap(), u64::MAX);
+
+        assert!(parse_amount("0.0000001", 6).is_err());
+        assert!(parse_amount("1.5", 0).is_err());
+        assert!(parse_amount("18446744073709551616", 0).is_err());
+        assert!(parse_amount("-1", 6).is_err());
+        assert!(parse_amount("1.2.3", 6).is_err());
+        assert!(parse_amount("abc", 6).is_err());
+    }
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format_amount(1_500_000, 6), "1.5");
+        assert_eq!(format_amount(1_000_000, 6), "1");
+        assert_eq!(format_amount(1, 6), "0.000001");
+        assert_eq!(format_amount(0, 6), "0");
+        assert_eq!(format_amount(42, 0), "42");
+        assert_eq!(format_amount(u64::MAX, 9), "18446744073.709551615");
+
+        for (amount, decimals) in [(1_234_567, 3), (10, 1), (u64::MAX, 0)] {
+            let formatted = format_amount(amount, decimals);
+            assert_eq!(parse_amount(&formatted, decimals).unwrap(), amount);
+        }
+    }
+
+    #[test]
+    fn test_parse_secret_hash() {
+        let sha256 = "ab".repeat(32);
+        assert_eq!(
+            parse_secret_hash(&sha256, HashAlgorithm::Sha256).unwrap(),
+            [0xab; 32]
+        );
+        assert_eq!(
+            parse_secret_hash(&sha256, HashAlgorithm::Keccak256).unwrap(),
+            [0xab; 32]
+        );
+
+        // The 20 byte digest of HASH160 is zero-padded
+        let hash160 = parse_secret_hash(&"ab".repeat(20), HashAlgorithm::Hash160).unwrap();
+        assert_eq!(hash160[..20], [0xab; 20]);
+        assert_eq!(hash160[20..], [0; 12]);
+
+        assert!(parse_secret_hash(&sha256, HashAlgorithm::Hash160).is_err());
+        assert!(parse_secret_hash(&"ab".repeat(20), HashAlgorithm::Sha256).is_err());
+        assert!(parse_secret_hash(&"ab".repeat(31), HashAlgorithm::Sha256).is_err());
+        assert!(parse_secret_hash(&"ab".repeat(33), HashAlgorithm::Keccak256).is_err());
+        assert!(parse_secret_hash("", HashAlgorithm::Hash160).is_err());
+        assert!(parse_secret_hash(&"zz".repeat(32), HashAlgorithm::Sha256).is_err());
+    }
+
+    #[test]
+    fn test_swap_mint_offset() {
+        let mint = Pubkey::new_unique();
+        let account = SwapAccount {
+            bump: 255,
+            expiry: u64::MAX,
+            timelock_kind: TimelockKind::Timestamp,
+            identity_pda_bump: 255,
+            token_vault: Pubkey::new_unique(),
+            vault_isolated: true,
+            rent_sponsor: Pubkey::new_unique(),
+            mint,
+            redeemer: Pubkey::new_unique(),
+            current_redeemer: Pubkey::new_unique(),
+            refundee: Pubkey::new_unique(),
+            current_refundee: Pubkey::new_unique(),
+            secret_hash: [1; 32],
+            hash_algorithm: HashAlgorithm::Sha256,
+            max_secret_len: 32,
+            swap_amount: 1,
+            escrowed_amount: 1,
+            fee_bps: 0,
+            fee_recipient: Pubkey::new_unique(),
+            relayer_tip: 0,
+            timelock: 1,
+            salt: 0,
+            order_id: [2; 32],
+        };
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        assert_eq!(data[SWAP_MINT_OFFSET..][..32], mint.to_bytes());
+    }
+}
@@ -0,0 +1,227 @@
+//! Decoding of the lifecycle events of atomic swaps.
+//! The program emits them through `emit_cpi!()` as an instruction invoking itself, whose data
+//! holds the 8-byte event instruction tag, followed by the 8-byte discriminator of the event and its
+//! Borsh serialization. Unlike logs, inner instructions are never truncated by validators.
+//! Events logged as `Program data: <base64>` lines can be decoded with [`parse_logs`], provided they
+//! have the current layouts. Those of prior versions of the program differ, e.g `Initiated` had no
+//! order id, and are rejected or misread, so transactions of these versions are not supported.
+
+use std::fmt;
+
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use crate::PROGRAM_ID;
+
+pub use solana_spl_swaps::{
+    Initiated, InstantRefunded, Redeemed, RedeemerTransferred, Refunded, RefundeeUpdated,
+};
+
+/// A lifecycle event of an atomic swap
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SwapEvent {
+    Initiated(Initiated),
+    Redeemed(Redeemed),
+    Refunded(Refunded),
+    InstantRefunded(InstantRefunded),
+    RefundeeUpdated(RefundeeUpdated),
+    RedeemerTransferred(RedeemerTransferred),
+}
+
+impl SwapEvent {
+    /// The order id of the swap, on which every lifecycle event can be joined
+    pub fn order_id(&self) -> [u8; 32] {
+        match self {
+            SwapEvent::Initiated(event) => event.order_id,
+            SwapEvent::Redeemed(event) => event.order_id,
+            SwapEvent::Refunded(event) => event.order_id,
+            SwapEvent::InstantRefunded(event) => event.order_id,
+            SwapEvent::RefundeeUpdated(event) => event.order_id,
+            SwapEvent::RedeemerTransferred(event) => event.order_id,
+        }
+    }
+}
+
+/// The events decoded from the logs of a transaction
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DecodedLogs {
+    /// The events emitted by this program, in order of emission
+    pub events: Vec<SwapEvent>,
+    /// Whether the logs were truncated by the validator, in which case events may be missing
+    pub truncated: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EventError {
+    /// A `Program data:` log line is not valid base64
+    InvalidBase64(String),
+    /// The data of an event with a known discriminator could not be deserialized
+    InvalidEvent(&'static str),
+    /// The logs do not follow the structure of program invocations, e.g a program returns
+    /// without having been invoked
+    MalformedLogs(String),
+}
+
+impl fmt::Display for EventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventError::InvalidBase64(data) => write!(f, "invalid base64 event data: {data}"),
+            EventError::InvalidEvent(name) => write!(f, "invalid {name} event data"),
+            EventError::MalformedLogs(line) => write!(f, "malformed log line: {line}"),
+        }
+    }
+}
+
+impl std::error::Error for EventError {}
+
+/// Decodes an event from its discriminator-prefixed data.
+/// Returns `None` for data not starting with the discriminator of a lifecycle event,
+/// such as other events of this program.
+pub fn decode_event(data: &[u8]) -> Result<Option<SwapEvent>, EventError> {
+    fn deserialize<T: AnchorDeserialize>(
+        mut body: &[u8],
+        name: &'static str,
+    ) -> Result<T, EventError> {
+        T::deserialize(&mut body).map_err(|_| EventError::InvalidEvent(name))
+    }
+
+    if data.len() < 8 {
+        return Ok(None);
+    }
+    let (discriminator, body) = data.split_at(8);
+    let event = if discriminator == Initiated::DISCRIMINATOR {
+        SwapEvent::Initiated(deserialize(body, "Initiated")?)
+    } else if discriminator == Redeemed::DISCRIMINATOR {
+        SwapEvent::Redeemed(deserialize(body, "Redeemed")?)
+    } else if discriminator == Refunded::DISCRIMINATOR {
+        SwapEvent::Refunded(deserialize(body, "Refunded")?)
+    } else if discriminator == InstantRefunded::DISCRIMINATOR {
+        SwapEvent::InstantRefunded(deserialize(body, "InstantRefunded")?)
+    } else if discriminator == RefundeeUpdated::DISCRIMINATOR {
+        SwapEvent::RefundeeUpdated(deserialize(body, "RefundeeUpdated")?)
+    } else if discriminator == RedeemerTransferred::DISCRIMINATOR {
+        SwapEvent::RedeemerTransferred(deserialize(body, "RedeemerTransferred")?)
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(event))
+}
+
+/// Decodes an event from the data of an instruction emitted through `emit_cpi!()`.
+/// Returns `None` for instructions other than events, and for events other than lifecycle events.
+pub fn decode_event_instruction(data: &[u8]) -> Result<Option<SwapEvent>, EventError> {
+    match data.strip_prefix(EVENT_IX_TAG_LE) {
+        Some(event) => decode_event(event),
+        None => Ok(None),
+    }
+}
+
+/// Decodes the events emitted by this program from the inner instructions of a transaction,
+/// given as pairs of invoked program and instruction data in order of invocation.
+/// Only the instructions invoking this program are decoded. As the program rejects event instructions
+/// not signed by its event authority PDA, these cannot be forged by other programs.
+/// `succeeded` is whether the transaction succeeded, i.e its status holds no error. The inner instructions
+/// of a failed transaction are recorded up to its failure, but none of their events took effect,
+/// so none are returned.
+pub fn parse_inner_instructions<'a, I>(
+    instructions: I,
+    succeeded: bool,
+) -> Result<Vec<SwapEvent>, EventError>
+where
+    I: IntoIterator<Item = (&'a Pubkey, &'a [u8])>,
+{
+    let mut events = Vec::new();
+    if !succeeded {
+        return Ok(events);
+    }
+    for (program, data) in instructions {
+        if *program != PROGRAM_ID {
+            continue;
+        }
+        if let Some(event) = decode_event_instruction(data)? {
+            events.push(event);
+        }
+    }
+    Ok(events)
+}
+
+/// Decodes the events emitted by this program from the logs of a transaction.
+/// Invocations are tracked such that only the `Program data:` lines logged by this program are decoded,
+/// whether it was invoked by the transaction itself or through a CPI by another program.
+/// The events of an invocation are only returned once it and its callers have succeeded, as a failed
+/// invocation fails the whole transaction. Those of invocations cut short by truncated logs are returned.
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Result<DecodedLogs, EventError> {
+    const PROGRAM_PREFIX: &str = "Program ";
+    const DATA_PREFIX: &str = "Program data: ";
+    const LOG_TRUNCATED: &str = "Log truncated";
+
+    let mut decoded = DecodedLogs::default();
+    // The invocations in progress, along with the events emitted within them so far
+    let mut invocations: Vec<(Pubkey, Vec<SwapEvent>)> = Vec::new();
+    for line in logs.iter().map(AsRef::as_ref) {
+        if line == LOG_TRUNCATED {
+            decoded.truncated = true;
+            break;
+        }
+        if let Some(data) = line.strip_prefix(DATA_PREFIX) {
+            let Some((_, events)) = invocations
+                .last_mut()
+                .filter(|(program, _)| *program == PROGRAM_ID)
+            else {
+                continue;
+            };
+            // `sol_log_data()` logs each of its fields as a space-separated base64 string
+            for field in data.split(' ') {
+                let bytes = STANDARD
+                    .decode(field)
+                    .map_err(|_| EventError::InvalidBase64(field.to_string()))?;
+                if let Some(event) = decode_event(&bytes)? {
+                    events.push(event);
+                }
+            }
+            continue;
+        }
+
+        let Some(rest) = line.strip_prefix(PROGRAM_PREFIX) else {
+            continue;
+        };
+        let mut words = rest.split(' ');
+        let (Some(program), Some(status)) = (words.next(), words.next()) else {
+            continue;
+        };
+        let Ok(program) = program.parse::<Pubkey>() else {
+            // e.g `Program log:` and `Program return:` lines
+            continue;
+        };
+        match status {
+            "invoke" => invocations.push((program, Vec::new())),
+            "success" | "failed:" => {
+                let events = match invocations.pop() {
+                    Some((invoked, events)) if invoked == program => events,
+                    _ => return Err(EventError::MalformedLogs(line.to_string())),
+                };
+                if status == "failed:" {
+                    // Neither the events of this invocation nor those of the prior instructions
+                    // of the transaction took effect
+                    if invocations.is_empty() {
+                        decoded.events.clear();
+                    }
+                    continue;
+                }
+                match invocations.last_mut() {
+                    Some((_, caller_events)) => caller_events.extend(events),
+                    None => decoded.events.extend(events),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    decoded
+        .events
+        .extend(invocations.into_iter().flat_map(|(_, events)| events));
+    Ok(decoded)
+}
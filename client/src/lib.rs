@@ -0,0 +1,730 @@
+//! A client for the `solana-spl-swaps` program, exposing the derivation of its PDAs,
+//! builders of its instructions and the decoding of its accounts and events.
+//! The seeds and layouts are taken from the program crate itself, such that they cannot drift apart.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{system_program, AccountDeserialize, InstructionData, ToAccountMetas};
+use solana_spl_swaps::{accounts, instruction};
+
+pub mod events;
+
+pub use solana_spl_swaps::{
+    compute_order_id, Config, HashAlgorithm, SwapAccount, TimelockKind, ID as PROGRAM_ID,
+    NATIVE_MINT,
+};
+
+/// The PDA used by the program as the authority of its token vaults
+pub fn identity_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[], &PROGRAM_ID)
+}
+
+/// The token vault shared by the swaps of `mint`
+pub fn token_vault_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[mint.as_ref()], &PROGRAM_ID)
+}
+
+/// The token vault of a swap initiated through `initiate_isolated()`
+pub fn isolated_token_vault_pda(swap_data: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[swap_data.as_ref()], &PROGRAM_ID)
+}
+
+/// The accounting of the token vault shared by the swaps of `mint`
+pub fn vault_state_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault_state", mint.as_ref()], &PROGRAM_ID)
+}
+
+/// The PDA signing the self-CPIs through which the program emits the lifecycle events of swaps
+pub fn event_authority_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"__event_authority"], &PROGRAM_ID)
+}
+
+/// The program configuration
+pub fn config_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config"], &PROGRAM_ID)
+}
+
+/// The policy of `mint`, or of native SOL if `mint` is `NATIVE_MINT`
+pub fn mint_policy_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"mint_policy", mint.as_ref()], &PROGRAM_ID)
+}
+
+/// The nonce tracker of the signed orders of `owner`
+pub fn signer_nonce_pda(owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"nonce", owner.as_ref()], &PROGRAM_ID)
+}
+
+/// The parameters identifying an atomic swap, from which its `swap_data` PDA and order id are derived
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapKey {
+    /// The mint of the swap, or `NATIVE_MINT` for atomic swaps of native SOL
+    pub mint: Pubkey,
+    /// The redeemer of the swap, as per the PDA seeds
+    pub redeemer: Pubkey,
+    /// The refundee of the swap, as per the PDA seeds
+    pub refundee: Pubkey,
+    pub secret_hash: [u8; 32],
+    pub swap_amount: u64,
+    pub timelock: u64,
+    pub salt: u64,
+}
+
+impl SwapKey {
+    /// The `swap_data` PDA holding the state of this swap
+    pub fn swap_data_pda(&self) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                self.mint.as_ref(),
+                self.redeemer.as_ref(),
+                self.refundee.as_ref(),
+                &self.secret_hash,
+                &self.swap_amount.to_le_bytes(),
+                &self.timelock.to_le_bytes(),
+                &self.salt.to_le_bytes(),
+            ],
+            &PROGRAM_ID,
+        )
+    }
+
+    /// The canonical identifier of this swap across chains
+    pub fn order_id(&self) -> [u8; 32] {
+        compute_order_id(
+            &self.mint,
+            &self.redeemer,
+            &self.refundee,
+            &self.secret_hash,
+            self.swap_amount,
+            self.timelock,
+            self.salt,
+        )
+    }
+}
+
+impl From<&SwapAccount> for SwapKey {
+    fn from(account: &SwapAccount) -> Self {
+        Self {
+            mint: account.mint,
+            redeemer: account.redeemer,
+            refundee: account.refundee,
+            secret_hash: account.secret_hash,
+            swap_amount: account.swap_amount,
+            timelock: account.timelock,
+            salt: account.salt,
+        }
+    }
+}
+
+/// The terms of a new atomic swap, beyond those identifying it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InitiateArgs {
+    pub timelock_kind: TimelockKind,
+    pub hash_algorithm: HashAlgorithm,
+    /// The maximum length in bytes of the secret accepted upon redeem
+    pub max_secret_len: u16,
+    /// The quantity of tokens paid out of the swap to the relayer submitting the redeem or refund
+    pub relayer_tip: u64,
+    /// Optional information regarding the destination chain, emitted as-is
+    pub destination_data: Option<Vec<u8>>,
+}
+
+impl Default for InitiateArgs {
+    /// A slot timelock and a 32-byte SHA-256 secret, without any relayer tip
+    fn default() -> Self {
+        Self {
+            timelock_kind: TimelockKind::Slots,
+            hash_algorithm: HashAlgorithm::Sha256,
+            max_secret_len: 32,
+            relayer_tip: 0,
+            destination_data: None,
+        }
+    }
+}
+
+/// Builds an `initiate()` instruction escrowing the swap in the token vault shared by its mint.
+/// `funder` and `rent_sponsor` must sign the transaction.
+pub fn initiate(
+    key: &SwapKey,
+    args: InitiateArgs,
+    funder: &Pubkey,
+    funder_token_account: &Pubkey,
+    rent_sponsor: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    let accounts = accounts::Initiate {
+        identity_pda: identity_pda().0,
+        swap_data: key.swap_data_pda().0,
+        token_vault: token_vault_pda(&key.mint).0,
+        vault_state: vault_state_pda(&key.mint).0,
+        config: config_pda().0,
+        mint_policy: mint_policy_pda(&key.mint).0,
+        funder: *funder,
+        funder_token_account: *funder_token_account,
+        mint: key.mint,
+        rent_sponsor: *rent_sponsor,
+        token_program: *token_program,
+        system_program: system_program::ID,
+        event_authority: event_authority_pda().0,
+        program: PROGRAM_ID,
+    };
+    let data = instruction::Initiate {
+        redeemer: key.redeemer,
+        refundee: key.refundee,
+        secret_hash: key.secret_hash,
+        swap_amount: key.swap_amount,
+        timelock: key.timelock,
+        salt: key.salt,
+        timelock_kind: args.timelock_kind,
+        hash_algorithm: args.hash_algorithm,
+        max_secret_len: args.max_secret_len,
+        relayer_tip: args.relayer_tip,
+        destination_data: args.destination_data,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Builds an `initiate_native()` instruction escrowing `key.swap_amount` lamports in the `swap_data` PDA.
+/// `key.mint` must be `NATIVE_MINT`. `funder` and `rent_sponsor` must sign the transaction.
+pub fn initiate_native(
+    key: &SwapKey,
+    args: InitiateArgs,
+    funder: &Pubkey,
+    rent_sponsor: &Pubkey,
+) -> Instruction {
+    let accounts = accounts::InitiateNative {
+        swap_data: key.swap_data_pda().0,
+        config: config_pda().0,
+        mint_policy: mint_policy_pda(&NATIVE_MINT).0,
+        funder: *funder,
+        rent_sponsor: *rent_sponsor,
+        system_program: system_program::ID,
+        event_authority: event_authority_pda().0,
+        program: PROGRAM_ID,
+    };
+    let data = instruction::InitiateNative {
+        redeemer: key.redeemer,
+        refundee: key.refundee,
+        secret_hash: key.secret_hash,
+        swap_amount: key.swap_amount,
+        timelock: key.timelock,
+        salt: key.salt,
+        timelock_kind: args.timelock_kind,
+        hash_algorithm: args.hash_algorithm,
+        max_secret_len: args.max_secret_len,
+        relayer_tip: args.relayer_tip,
+        destination_data: args.destination_data,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// An atomic swap as stored on-chain, from which the instructions completing it are built
+#[derive(Clone)]
+pub struct Swap {
+    /// The `swap_data` PDA
+    pub address: Pubkey,
+    pub account: SwapAccount,
+}
+
+impl Swap {
+    /// Decodes the data of the `swap_data` PDA at `address`
+    pub fn decode(address: Pubkey, data: &[u8]) -> anchor_lang::Result<Self> {
+        Ok(Self {
+            address,
+            account: decode_swap_account(data)?,
+        })
+    }
+
+    /// Builds a `redeem()` instruction paying the current redeemer's `redeemer_token_account`.
+    /// `fee_token_account`, the associated token account of the fee recipient, is required if the swap
+    /// charges a protocol fee, and `relayer_token_account` if it pays a relayer tip.
+    /// `relayer` must sign the transaction.
+    pub fn redeem(
+        &self,
+        secret: Vec<u8>,
+        redeemer_token_account: &Pubkey,
+        fee_token_account: Option<Pubkey>,
+        relayer: &Pubkey,
+        relayer_token_account: Option<Pubkey>,
+        token_program: &Pubkey,
+    ) -> Instruction {
+        let accounts = accounts::Redeem {
+            identity_pda: identity_pda().0,
+            swap_data: self.address,
+            mint: self.account.mint,
+            token_vault: self.account.token_vault,
+            vault_state: vault_state_pda(&self.account.mint).0,
+            redeemer_token_account: *redeemer_token_account,
+            fee_token_account,
+            relayer: *relayer,
+            relayer_token_account,
+            rent_sponsor: self.account.rent_sponsor,
+            token_program: *token_program,
+            event_authority: event_authority_pda().0,
+            program: PROGRAM_ID,
+        };
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: accounts.to_account_metas(None),
+            data: instruction::Redeem { secret }.data(),
+        }
+    }
+
+    /// Builds a `refund()` instruction paying the current refundee's `refundee_token_account`.
+    /// `relayer_token_account` is required if the swap pays a relayer tip. `relayer` must sign the transaction.
+    pub fn refund(
+        &self,
+        refundee_token_account: &Pubkey,
+        relayer: &Pubkey,
+        relayer_token_account: Option<Pubkey>,
+        token_program: &Pubkey,
+    ) -> Instruction {
+        let accounts = accounts::Refund {
+            identity_pda: identity_pda().0,
+            swap_data: self.address,
+            mint: self.account.mint,
+            token_vault: self.account.token_vault,
+            vault_state: vault_state_pda(&self.account.mint).0,
+            refundee_token_account: *refundee_token_account,
+            relayer: *relayer,
+            relayer_token_account,
+            rent_sponsor: self.account.rent_sponsor,
+            token_program: *token_program,
+            event_authority: event_authority_pda().0,
+            program: PROGRAM_ID,
+        };
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: accounts.to_account_metas(None),
+            data: instruction::Refund {}.data(),
+        }
+    }
+
+    /// Builds an `instant_refund()` instruction paying the current refundee's `refundee_token_account`.
+    /// The current redeemer must sign the transaction.
+    pub fn instant_refund(
+        &self,
+        refundee_token_account: &Pubkey,
+        token_program: &Pubkey,
+    ) -> Instruction {
+        let accounts = accounts::InstantRefund {
+            identity_pda: identity_pda().0,
+            swap_data: self.address,
+            mint: self.account.mint,
+            token_vault: self.account.token_vault,
+            vault_state: vault_state_pda(&self.account.mint).0,
+            refundee_token_account: *refundee_token_account,
+            redeemer: self.account.current_redeemer,
+            rent_sponsor: self.account.rent_sponsor,
+            token_program: *token_program,
+            event_authority: event_authority_pda().0,
+            program: PROGRAM_ID,
+        };
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: accounts.to_account_metas(None),
+            data: instruction::InstantRefund {}.data(),
+        }
+    }
+
+    /// Builds a `redeem_native()` instruction paying the current redeemer in lamports.
+    /// `fee_recipient` is required if the swap charges a protocol fee. `relayer` must sign the transaction.
+    pub fn redeem_native(
+        &self,
+        secret: Vec<u8>,
+        fee_recipient: Option<Pubkey>,
+        relayer: &Pubkey,
+    ) -> Instruction {
+        let accounts = accounts::RedeemNative {
+            swap_data: self.address,
+            redeemer: self.account.current_redeemer,
+            fee_recipient,
+            relayer: *relayer,
+            rent_sponsor: self.account.rent_sponsor,
+            event_authority: event_authority_pda().0,
+            program: PROGRAM_ID,
+        };
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: accounts.to_account_metas(None),
+            data: instruction::RedeemNative { secret }.data(),
+        }
+    }
+
+    /// Builds a `refund_native()` instruction paying the current refundee in lamports.
+    /// `relayer` must sign the transaction.
+    pub fn refund_native(&self, relayer: &Pubkey) -> Instruction {
+        let accounts = accounts::RefundNative {
+            swap_data: self.address,
+            refundee: self.account.current_refundee,
+            relayer: *relayer,
+            rent_sponsor: self.account.rent_sponsor,
+            event_authority: event_authority_pda().0,
+            program: PROGRAM_ID,
+        };
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: accounts.to_account_metas(None),
+            data: instruction::RefundNative {}.data(),
+        }
+    }
+
+    /// Builds an `instant_refund_native()` instruction paying the current refundee in lamports.
+    /// The current redeemer must sign the transaction.
+    pub fn instant_refund_native(&self) -> Instruction {
+        let accounts = accounts::InstantRefundNative {
+            swap_data: self.address,
+            refundee: self.account.current_refundee,
+            redeemer: self.account.current_redeemer,
+            rent_sponsor: self.account.rent_sponsor,
+            event_authority: event_authority_pda().0,
+            program: PROGRAM_ID,
+        };
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: accounts.to_account_metas(None),
+            data: instruction::InstantRefundNative {}.data(),
+        }
+    }
+}
+
+/// Decodes the data of a `swap_data` PDA, checking its discriminator
+pub fn decode_swap_account(data: &[u8]) -> anchor_lang::Result<SwapAccount> {
+    SwapAccount::try_deserialize(&mut &data[..])
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::AccountMeta;
+    use anchor_lang::{AccountSerialize, Discriminator};
+
+    use super::*;
+
+    fn key(byte: u8) -> Pubkey {
+        Pubkey::new_from_array([byte; 32])
+    }
+
+    fn swap_key() -> SwapKey {
+        SwapKey {
+            mint: key(1),
+            redeemer: key(2),
+            refundee: key(3),
+            secret_hash: [4; 32],
+            swap_amount: 1_000_000,
+            timelock: 3_600,
+            salt: 42,
+        }
+    }
+
+    fn swap_account() -> SwapAccount {
+        let swap_key = swap_key();
+        SwapAccount {
+            bump: swap_key.swap_data_pda().1,
+            expiry: 1_700_003_600,
+            timelock_kind: TimelockKind::Seconds,
+            identity_pda_bump: identity_pda().1,
+            token_vault: token_vault_pda(&swap_key.mint).0,
+            vault_isolated: false,
+            rent_sponsor: key(5),
+            mint: swap_key.mint,
+            redeemer: swap_key.redeemer,
+            current_redeemer: key(6),
+            refundee: swap_key.refundee,
+            current_refundee: key(7),
+            secret_hash: swap_key.secret_hash,
+            hash_algorithm: HashAlgorithm::Keccak256,
+            max_secret_len: 64,
+            swap_amount: swap_key.swap_amount,
+            escrowed_amount: 999_000,
+            fee_bps: 30,
+            fee_recipient: key(8),
+            relayer_tip: 1_000,
+            timelock: swap_key.timelock,
+            salt: swap_key.salt,
+            order_id: swap_key.order_id(),
+        }
+    }
+
+    fn swap() -> Swap {
+        Swap {
+            address: swap_key().swap_data_pda().0,
+            account: swap_account(),
+        }
+    }
+
+    /// Asserts that `pda` is derived from `seeds`, as declared by the constraints of the program
+    fn assert_derived(pda: (Pubkey, u8), seeds: &[&[u8]]) {
+        let (address, bump) = pda;
+        let bump = [bump];
+        let seeds = [seeds, &[&bump[..]]].concat();
+        assert_eq!(
+            Pubkey::create_program_address(&seeds, &PROGRAM_ID),
+            Ok(address)
+        );
+    }
+
+    /// The account metas appended by `#[event_cpi]`
+    fn event_cpi_metas() -> [AccountMeta; 2] {
+        [
+            AccountMeta::new_readonly(event_authority_pda().0, false),
+            AccountMeta::new_readonly(PROGRAM_ID, false),
+        ]
+    }
+
+    #[test]
+    fn test_pdas() {
+        let swap_key = swap_key();
+        let swap_data = swap_key.swap_data_pda().0;
+        assert_derived(identity_pda(), &[]);
+        assert_derived(token_vault_pda(&swap_key.mint), &[swap_key.mint.as_ref()]);
+        assert_derived(isolated_token_vault_pda(&swap_data), &[swap_data.as_ref()]);
+        assert_derived(
+            vault_state_pda(&swap_key.mint),
+            &[b"vault_state", swap_key.mint.as_ref()],
+        );
+        assert_derived(event_authority_pda(), &[b"__event_authority"]);
+        assert_derived(config_pda(), &[b"config"]);
+        assert_derived(
+            mint_policy_pda(&swap_key.mint),
+            &[b"mint_policy", swap_key.mint.as_ref()],
+        );
+        assert_derived(signer_nonce_pda(&key(9)), &[b"nonce", key(9).as_ref()]);
+        assert_derived(
+            swap_key.swap_data_pda(),
+            &[
+                swap_key.mint.as_ref(),
+                swap_key.redeemer.as_ref(),
+                swap_key.refundee.as_ref(),
+                &swap_key.secret_hash,
+                &1_000_000u64.to_le_bytes(),
+                &3_600u64.to_le_bytes(),
+                &42u64.to_le_bytes(),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_initiate() {
+        let swap_key = swap_key();
+        let (funder, funder_token_account, rent_sponsor, token_program) =
+            (key(10), key(11), key(12), key(13));
+        let ix = initiate(
+            &swap_key,
+            InitiateArgs::default(),
+            &funder,
+            &funder_token_account,
+            &rent_sponsor,
+            &token_program,
+        );
+
+        let mut expected = vec![
+            AccountMeta::new_readonly(identity_pda().0, false),
+            AccountMeta::new(swap_key.swap_data_pda().0, false),
+            AccountMeta::new(token_vault_pda(&swap_key.mint).0, false),
+            AccountMeta::new(vault_state_pda(&swap_key.mint).0, false),
+            AccountMeta::new_readonly(config_pda().0, false),
+            AccountMeta::new_readonly(mint_policy_pda(&swap_key.mint).0, false),
+            AccountMeta::new_readonly(funder, true),
+            AccountMeta::new(funder_token_account, false),
+            AccountMeta::new_readonly(swap_key.mint, false),
+            AccountMeta::new(rent_sponsor, true),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ];
+        expected.extend(event_cpi_metas());
+        assert_eq!(ix.program_id, PROGRAM_ID);
+        assert_eq!(ix.accounts, expected);
+        assert_eq!(ix.data[..8], *instruction::Initiate::DISCRIMINATOR);
+    }
+
+    #[test]
+    fn test_redeem() {
+        let swap = swap();
+        let (redeemer_token_account, fee_token_account, relayer, token_program) =
+            (key(10), key(11), key(12), key(13));
+        let ix = swap.redeem(
+            vec![0; 32],
+            &redeemer_token_account,
+            Some(fee_token_account),
+            &relayer,
+            None,
+            &token_program,
+        );
+
+        // Omitted optional accounts are substituted by the program id
+        let mut expected = vec![
+            AccountMeta::new_readonly(identity_pda().0, false),
+            AccountMeta::new(swap.address, false),
+            AccountMeta::new(swap.account.mint, false),
+            AccountMeta::new(swap.account.token_vault, false),
+            AccountMeta::new(vault_state_pda(&swap.account.mint).0, false),
+            AccountMeta::new(redeemer_token_account, false),
+            AccountMeta::new(fee_token_account, false),
+            AccountMeta::new_readonly(relayer, true),
+            AccountMeta::new_readonly(PROGRAM_ID, false),
+            AccountMeta::new(swap.account.rent_sponsor, false),
+            AccountMeta::new_readonly(token_program, false),
+        ];
+        expected.extend(event_cpi_metas());
+        assert_eq!(ix.accounts, expected);
+        assert_eq!(ix.data[..8], *instruction::Redeem::DISCRIMINATOR);
+    }
+
+    #[test]
+    fn test_refund() {
+        let swap = swap();
+        let (refundee_token_account, relayer, relayer_token_account, token_program) =
+            (key(10), key(11), key(12), key(13));
+        let ix = swap.refund(
+            &refundee_token_account,
+            &relayer,
+            Some(relayer_token_account),
+            &token_program,
+        );
+
+        let mut expected = vec![
+            AccountMeta::new_readonly(identity_pda().0, false),
+            AccountMeta::new(swap.address, false),
+            AccountMeta::new(swap.account.mint, false),
+            AccountMeta::new(swap.account.token_vault, false),
+            AccountMeta::new(vault_state_pda(&swap.account.mint).0, false),
+            AccountMeta::new(refundee_token_account, false),
+            AccountMeta::new_readonly(relayer, true),
+            AccountMeta::new(relayer_token_account, false),
+            AccountMeta::new(swap.account.rent_sponsor, false),
+            AccountMeta::new_readonly(token_program, false),
+        ];
+        expected.extend(event_cpi_metas());
+        assert_eq!(ix.accounts, expected);
+        assert_eq!(ix.data, instruction::Refund::DISCRIMINATOR);
+    }
+
+    #[test]
+    fn test_instant_refund() {
+        let swap = swap();
+        let (refundee_token_account, token_program) = (key(10), key(11));
+        let ix = swap.instant_refund(&refundee_token_account, &token_program);
+
+        // The current redeemer signs rather than the original one
+        let mut expected = vec![
+            AccountMeta::new_readonly(identity_pda().0, false),
+            AccountMeta::new(swap.address, false),
+            AccountMeta::new(swap.account.mint, false),
+            AccountMeta::new(swap.account.token_vault, false),
+            AccountMeta::new(vault_state_pda(&swap.account.mint).0, false),
+            AccountMeta::new(refundee_token_account, false),
+            AccountMeta::new(swap.account.current_redeemer, true),
+            AccountMeta::new(swap.account.rent_sponsor, false),
+            AccountMeta::new_readonly(token_program, false),
+        ];
+        expected.extend(event_cpi_metas());
+        assert_eq!(ix.accounts, expected);
+        assert_eq!(ix.data, instruction::InstantRefund::DISCRIMINATOR);
+    }
+
+    #[test]
+    fn test_initiate_native() {
+        let swap_key = SwapKey {
+            mint: NATIVE_MINT,
+            ..swap_key()
+        };
+        let (funder, rent_sponsor) = (key(10), key(11));
+        let ix = initiate_native(&swap_key, InitiateArgs::default(), &funder, &rent_sponsor);
+
+        let mut expected = vec![
+            AccountMeta::new(swap_key.swap_data_pda().0, false),
+            AccountMeta::new_readonly(config_pda().0, false),
+            AccountMeta::new_readonly(mint_policy_pda(&NATIVE_MINT).0, false),
+            AccountMeta::new(funder, true),
+            AccountMeta::new(rent_sponsor, true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ];
+        expected.extend(event_cpi_metas());
+        assert_eq!(ix.program_id, PROGRAM_ID);
+        assert_eq!(ix.accounts, expected);
+        assert_eq!(ix.data[..8], *instruction::InitiateNative::DISCRIMINATOR);
+    }
+
+    #[test]
+    fn test_redeem_native() {
+        let swap = swap();
+        let relayer = key(10);
+        let ix = swap.redeem_native(vec![0; 32], None, &relayer);
+
+        // The lamports are paid to the current redeemer rather than the original one
+        let mut expected = vec![
+            AccountMeta::new(swap.address, false),
+            AccountMeta::new(swap.account.current_redeemer, false),
+            AccountMeta::new_readonly(PROGRAM_ID, false),
+            AccountMeta::new(relayer, true),
+            AccountMeta::new(swap.account.rent_sponsor, false),
+        ];
+        expected.extend(event_cpi_metas());
+        assert_eq!(ix.accounts, expected);
+        assert_eq!(ix.data[..8], *instruction::RedeemNative::DISCRIMINATOR);
+
+        let ix = swap.redeem_native(vec![0; 32], Some(swap.account.fee_recipient), &relayer);
+        assert_eq!(
+            ix.accounts[2],
+            AccountMeta::new(swap.account.fee_recipient, false)
+        );
+    }
+
+    #[test]
+    fn test_refund_native() {
+        let swap = swap();
+        let relayer = key(10);
+        let ix = swap.refund_native(&relayer);
+
+        let mut expected = vec![
+            AccountMeta::new(swap.address, false),
+            AccountMeta::new(swap.account.current_refundee, false),
+            AccountMeta::new(relayer, true),
+            AccountMeta::new(swap.account.rent_sponsor, false),
+        ];
+        expected.extend(event_cpi_metas());
+        assert_eq!(ix.accounts, expected);
+        assert_eq!(ix.data, instruction::RefundNative::DISCRIMINATOR);
+    }
+
+    #[test]
+    fn test_instant_refund_native() {
+        let swap = swap();
+        let ix = swap.instant_refund_native();
+
+        let mut expected = vec![
+            AccountMeta::new(swap.address, false),
+            AccountMeta::new(swap.account.current_refundee, false),
+            AccountMeta::new_readonly(swap.account.current_redeemer, true),
+            AccountMeta::new(swap.account.rent_sponsor, false),
+        ];
+        expected.extend(event_cpi_metas());
+        assert_eq!(ix.accounts, expected);
+        assert_eq!(ix.data, instruction::InstantRefundNative::DISCRIMINATOR);
+    }
+
+    #[test]
+    fn test_decode_swap_account() {
+        let account = swap_account();
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+
+        let decoded = decode_swap_account(&data).unwrap();
+        let mut redecoded = Vec::new();
+        decoded.try_serialize(&mut redecoded).unwrap();
+        assert_eq!(redecoded, data);
+        assert_eq!(SwapKey::from(&decoded), swap_key());
+        assert_eq!(decoded.current_redeemer, key(6));
+        assert_eq!(decoded.fee_recipient, key(8));
+
+        // The data of other accounts is rejected
+        data[..8].copy_from_slice(Config::DISCRIMINATOR);
+        assert!(decode_swap_account(&data).is_err());
+    }
+}
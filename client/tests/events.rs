@@ -0,0 +1,530 @@
+//! Test vectors of the lifecycle events, built from the IDL of the program.
+//! `idl/solana_spl_swaps.json` is a copy of the IDL written by `anchor build` to `target/idl/`.
+//! Each vector is the discriminator of the event followed by the field values returned by `fields()`,
+//! serialized as per the field types of the event in the IDL, and must decode to the event returned
+//! by the function of the same name. A change to the layout of an event therefore fails these tests
+//! until the copy of the IDL is updated.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::{Event, InstructionData};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::{json, Value};
+use solana_spl_swaps::{HashAlgorithm, TimelockKind};
+use solana_spl_swaps_client::events::{
+    decode_event, parse_inner_instructions, parse_logs, EventError, Initiated, InstantRefunded,
+    Redeemed, RedeemerTransferred, Refunded, RefundeeUpdated, SwapEvent,
+};
+use solana_spl_swaps_client::PROGRAM_ID;
+
+const IDL: &str = include_str!("idl/solana_spl_swaps.json");
+
+// Sha256("anchor:event")[..8], prefixing the data of the instructions emitted through `emit_cpi!()`
+const EVENT_IX_TAG: [u8; 8] = [228, 69, 165, 46, 81, 203, 154, 29];
+
+const OTHER_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+fn key(byte: u8) -> Pubkey {
+    Pubkey::new_from_array([byte; 32])
+}
+
+fn initiated() -> Initiated {
+    Initiated {
+        mint: key(1),
+        redeemer: key(2),
+        refundee: key(3),
+        secret_hash: [4; 32],
+        hash_algorithm: HashAlgorithm::Keccak256,
+        max_secret_len: 32,
+        swap_amount: 1_000_000,
+        escrowed_amount: 999_000,
+        relayer_tip: 1_000,
+        timelock: 3_600,
+        salt: 42,
+        order_id: [5; 32],
+        timelock_kind: TimelockKind::Seconds,
+        expiry: 1_700_003_600,
+        destination_data: Some(vec![6, 7, 8]),
+        funder: key(9),
+    }
+}
+
+fn redeemed() -> Redeemed {
+    Redeemed {
+        mint: key(1),
+        redeemer: key(2),
+        refundee: key(3),
+        secret: vec![10; 20],
+        swap_amount: 1_000_000,
+        fee_amount: 2_000,
+        relayer_tip: 1_000,
+        timelock: 3_600,
+        salt: 42,
+        order_id: [5; 32],
+        current_redeemer: key(11),
+        destination: key(12),
+    }
+}
+
+fn refunded() -> Refunded {
+    Refunded {
+        mint: key(1),
+        redeemer: key(2),
+        refundee: key(3),
+        secret_hash: [4; 32],
+        swap_amount: 1_000_000,
+        relayer_tip: 1_000,
+        timelock: 3_600,
+        salt: 42,
+        order_id: [5; 32],
+        expiry: 1_700_003_600,
+        current_refundee: key(13),
+        destination: key(14),
+    }
+}
+
+fn instant_refunded() -> InstantRefunded {
+    InstantRefunded {
+        mint: key(1),
+        redeemer: key(2),
+        refundee: key(3),
+        secret_hash: [4; 32],
+        swap_amount: 1_000_000,
+        timelock: 3_600,
+        salt: 42,
+        order_id: [5; 32],
+        current_refundee: key(13),
+        destination: key(14),
+    }
+}
+
+fn refundee_updated() -> RefundeeUpdated {
+    RefundeeUpdated {
+        order_id: [5; 32],
+        previous_refundee: key(3),
+        new_refundee: key(13),
+    }
+}
+
+fn redeemer_transferred() -> RedeemerTransferred {
+    RedeemerTransferred {
+        order_id: [5; 32],
+        previous_redeemer: key(2),
+        new_redeemer: key(11),
+    }
+}
+
+fn invoke(program: &str, depth: u8) -> String {
+    format!("Program {program} invoke [{depth}]")
+}
+
+fn success(program: &str) -> String {
+    format!("Program {program} success")
+}
+
+fn failed(program: &str) -> String {
+    format!("Program {program} failed: custom program error: 0x1")
+}
+
+fn data(event: &str) -> String {
+    format!("Program data: {}", STANDARD.encode(vector(event)))
+}
+
+fn event_instruction(event: &str) -> Vec<u8> {
+    [EVENT_IX_TAG.as_slice(), &vector(event)].concat()
+}
+
+/// The lifecycle events with a vector, along with their names
+fn lifecycle_events() -> Vec<(&'static str, SwapEvent)> {
+    vec![
+        ("Initiated", SwapEvent::Initiated(initiated())),
+        ("Redeemed", SwapEvent::Redeemed(redeemed())),
+        ("Refunded", SwapEvent::Refunded(refunded())),
+        (
+            "InstantRefunded",
+            SwapEvent::InstantRefunded(instant_refunded()),
+        ),
+        (
+            "RefundeeUpdated",
+            SwapEvent::RefundeeUpdated(refundee_updated()),
+        ),
+        (
+            "RedeemerTransferred",
+            SwapEvent::RedeemerTransferred(redeemer_transferred()),
+        ),
+    ]
+}
+
+/// The field values of the vector of the event `name`, keyed by their names in the IDL
+fn fields(name: &str) -> Value {
+    match name {
+        "Initiated" => json!({
+            "mint": key(1).to_string(),
+            "redeemer": key(2).to_string(),
+            "refundee": key(3).to_string(),
+            "secret_hash": vec![4; 32],
+            "hash_algorithm": "Keccak256",
+            "max_secret_len": 32,
+            "swap_amount": 1_000_000,
+            "escrowed_amount": 999_000,
+            "relayer_tip": 1_000,
+            "timelock": 3_600,
+            "salt": 42,
+            "order_id": vec![5; 32],
+            "timelock_kind": "Seconds",
+            "expiry": 1_700_003_600,
+            "destination_data": [6, 7, 8],
+            "funder": key(9).to_string(),
+        }),
+        "Redeemed" => json!({
+            "mint": key(1).to_string(),
+            "redeemer": key(2).to_string(),
+            "refundee": key(3).to_string(),
+            "secret": vec![10; 20],
+            "swap_amount": 1_000_000,
+            "fee_amount": 2_000,
+            "relayer_tip": 1_000,
+            "timelock": 3_600,
+            "salt": 42,
+            "order_id": vec![5; 32],
+            "current_redeemer": key(11).to_string(),
+            "destination": key(12).to_string(),
+        }),
+        "Refunded" => json!({
+            "mint": key(1).to_string(),
+            "redeemer": key(2).to_string(),
+            "refundee": key(3).to_string(),
+            "secret_hash": vec![4; 32],
+            "swap_amount": 1_000_000,
+            "relayer_tip": 1_000,
+            "timelock": 3_600,
+            "salt": 42,
+            "order_id": vec![5; 32],
+            "expiry": 1_700_003_600,
+            "current_refundee": key(13).to_string(),
+            "destination": key(14).to_string(),
+        }),
+        "InstantRefunded" => json!({
+            "mint": key(1).to_string(),
+            "redeemer": key(2).to_string(),
+            "refundee": key(3).to_string(),
+            "secret_hash": vec![4; 32],
+            "swap_amount": 1_000_000,
+            "timelock": 3_600,
+            "salt": 42,
+            "order_id": vec![5; 32],
+            "current_refundee": key(13).to_string(),
+            "destination": key(14).to_string(),
+        }),
+        "RefundeeUpdated" => json!({
+            "order_id": vec![5; 32],
+            "previous_refundee": key(3).to_string(),
+            "new_refundee": key(13).to_string(),
+        }),
+        "RedeemerTransferred" => json!({
+            "order_id": vec![5; 32],
+            "previous_redeemer": key(2).to_string(),
+            "new_redeemer": key(11).to_string(),
+        }),
+        _ => panic!("no vector of the event {name}"),
+    }
+}
+
+fn idl() -> Value {
+    serde_json::from_str(IDL).unwrap()
+}
+
+/// The discriminator of the event `name` in the IDL
+fn discriminator(name: &str) -> Vec<u8> {
+    let idl = idl();
+    let event = idl["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|event| event["name"] == name)
+        .unwrap_or_else(|| panic!("no event {name} in the IDL"));
+    serde_json::from_value(event["discriminator"].clone()).unwrap()
+}
+
+/// The vector of the event `name`: its discriminator followed by its field values, serialized as per the IDL
+fn vector(name: &str) -> Vec<u8> {
+    let mut data = discriminator(name);
+    serialize(
+        &idl(),
+        &json!({ "defined": { "name": name } }),
+        &fields(name),
+        &mut data,
+    );
+    data
+}
+
+/// Borsh serializes `value` as the IDL type `ty`
+fn serialize(idl: &Value, ty: &Value, value: &Value, out: &mut Vec<u8>) {
+    if let Some(ty) = ty.as_str() {
+        match ty {
+            "u8" => out.push(value.as_u64().unwrap().try_into().unwrap()),
+            "u16" => out.extend(
+                u16::try_from(value.as_u64().unwrap())
+                    .unwrap()
+                    .to_le_bytes(),
+            ),
+            "u32" => out.extend(
+                u32::try_from(value.as_u64().unwrap())
+                    .unwrap()
+                    .to_le_bytes(),
+            ),
+            "u64" => out.extend(value.as_u64().unwrap().to_le_bytes()),
+            "i64" => out.extend(value.as_i64().unwrap().to_le_bytes()),
+            "bool" => out.push(value.as_bool().unwrap().into()),
+            "pubkey" => out.extend(
+                value
+                    .as_str()
+                    .unwrap()
+                    .parse::<Pubkey>()
+                    .unwrap()
+                    .to_bytes(),
+            ),
+            "bytes" => serialize(idl, &json!({ "vec": "u8" }), value, out),
+            _ => panic!("unsupported IDL type {ty}"),
+        }
+    } else if let Some([item, len]) = ty["array"].as_array().map(Vec::as_slice) {
+        let items = value.as_array().unwrap();
+        assert_eq!(items.len() as u64, len.as_u64().unwrap());
+        for value in items {
+            serialize(idl, item, value, out);
+        }
+    } else if let Some(item) = ty.get("vec") {
+        let items = value.as_array().unwrap();
+        out.extend(u32::try_from(items.len()).unwrap().to_le_bytes());
+        for value in items {
+            serialize(idl, item, value, out);
+        }
+    } else if let Some(item) = ty.get("option") {
+        if value.is_null() {
+            out.push(0);
+        } else {
+            out.push(1);
+            serialize(idl, item, value, out);
+        }
+    } else if let Some(name) = ty["defined"]["name"].as_str() {
+        let def = idl["types"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|def| def["name"] == name)
+            .unwrap_or_else(|| panic!("no type {name} in the IDL"));
+        match def["type"]["kind"].as_str().unwrap() {
+            "struct" => {
+                let fields = def["type"]["fields"].as_array().unwrap();
+                let values = value.as_object().unwrap();
+                assert_eq!(values.len(), fields.len(), "fields of {name}");
+                for field in fields {
+                    let field_name = field["name"].as_str().unwrap();
+                    let value = values
+                        .get(field_name)
+                        .unwrap_or_else(|| panic!("no value of the field {name}.{field_name}"));
+                    serialize(idl, &field["type"], value, out);
+                }
+            }
+            "enum" => {
+                // Only unit variants are supported, given by their names
+                let variants = def["type"]["variants"].as_array().unwrap();
+                let index = variants
+                    .iter()
+                    .position(|variant| variant["name"] == *value)
+                    .unwrap_or_else(|| panic!("no variant {value} of {name}"));
+                out.push(index.try_into().unwrap());
+            }
+            kind => panic!("unsupported kind {kind} of {name}"),
+        }
+    } else {
+        panic!("unsupported IDL type {ty}");
+    }
+}
+
+#[test]
+fn vectors_match_the_idl_and_the_events() {
+    for event in idl()["events"].as_array().unwrap() {
+        let name = event["name"].as_str().unwrap();
+        let preimage = format!("event:{name}");
+        assert_eq!(
+            hash(preimage.as_bytes()).to_bytes()[..8],
+            discriminator(name)
+        );
+    }
+    for (name, event) in lifecycle_events() {
+        let data = match &event {
+            SwapEvent::Initiated(event) => event.data(),
+            SwapEvent::Redeemed(event) => event.data(),
+            SwapEvent::Refunded(event) => event.data(),
+            SwapEvent::InstantRefunded(event) => event.data(),
+            SwapEvent::RefundeeUpdated(event) => event.data(),
+            SwapEvent::RedeemerTransferred(event) => event.data(),
+        };
+        assert_eq!(data, vector(name), "{name}");
+        assert_eq!(decode_event(&vector(name)), Ok(Some(event)), "{name}");
+    }
+}
+
+#[test]
+fn decodes_every_lifecycle_event() {
+    let program = PROGRAM_ID.to_string();
+    let logs = vec![
+        invoke(&program, 1),
+        "Program log: Instruction: Initiate".to_string(),
+        data("Initiated"),
+        data("Redeemed"),
+        data("Refunded"),
+        data("InstantRefunded"),
+        data("RefundeeUpdated"),
+        data("RedeemerTransferred"),
+        format!("Program {program} consumed 20000 of 200000 compute units"),
+        success(&program),
+    ];
+
+    let decoded = parse_logs(&logs).unwrap();
+    assert!(!decoded.truncated);
+    assert_eq!(
+        decoded.events,
+        vec![
+            SwapEvent::Initiated(initiated()),
+            SwapEvent::Redeemed(redeemed()),
+            SwapEvent::Refunded(refunded()),
+            SwapEvent::InstantRefunded(instant_refunded()),
+            SwapEvent::RefundeeUpdated(refundee_updated()),
+            SwapEvent::RedeemerTransferred(redeemer_transferred()),
+        ]
+    );
+    assert!(decoded
+        .events
+        .iter()
+        .all(|event| event.order_id() == [5; 32]));
+}
+
+#[test]
+fn decodes_events_of_nested_invocations_only_from_this_program() {
+    let program = PROGRAM_ID.to_string();
+    let logs = vec![
+        invoke(OTHER_PROGRAM, 1),
+        // Logged by another program, hence ignored
+        data("Initiated"),
+        invoke(&program, 2),
+        invoke(OTHER_PROGRAM, 3),
+        success(OTHER_PROGRAM),
+        data("Redeemed"),
+        success(&program),
+        success(OTHER_PROGRAM),
+    ];
+
+    let decoded = parse_logs(&logs).unwrap();
+    assert_eq!(decoded.events, vec![SwapEvent::Redeemed(redeemed())]);
+}
+
+#[test]
+fn drops_events_of_failed_transactions() {
+    let program = PROGRAM_ID.to_string();
+    // The invocation emitting the event fails, failing its caller
+    let logs = vec![
+        invoke(OTHER_PROGRAM, 1),
+        invoke(&program, 2),
+        data("Redeemed"),
+        failed(&program),
+        failed(OTHER_PROGRAM),
+    ];
+    assert_eq!(parse_logs(&logs).unwrap().events, vec![]);
+
+    // The invocation emitting the event succeeds, but a later invocation fails
+    let logs = vec![
+        invoke(&program, 1),
+        data("Initiated"),
+        invoke(OTHER_PROGRAM, 2),
+        failed(OTHER_PROGRAM),
+        failed(&program),
+    ];
+    assert_eq!(parse_logs(&logs).unwrap().events, vec![]);
+
+    // A later instruction of the transaction fails
+    let logs = vec![
+        invoke(&program, 1),
+        data("Initiated"),
+        success(&program),
+        invoke(&program, 1),
+        data("Redeemed"),
+        failed(&program),
+    ];
+    assert_eq!(parse_logs(&logs).unwrap().events, vec![]);
+}
+
+#[test]
+fn detects_truncated_logs() {
+    let program = PROGRAM_ID.to_string();
+    let logs = vec![
+        invoke(&program, 1),
+        data("Initiated"),
+        "Log truncated".to_string(),
+    ];
+
+    let decoded = parse_logs(&logs).unwrap();
+    assert!(decoded.truncated);
+    assert_eq!(decoded.events, vec![SwapEvent::Initiated(initiated())]);
+}
+
+#[test]
+fn ignores_other_events_and_rejects_corrupt_data() {
+    let mut reconciled = discriminator("Reconciled");
+    reconciled.extend_from_slice(&[0; 72]);
+    assert_eq!(decode_event(&reconciled), Ok(None));
+
+    let mut truncated_initiated = discriminator("Initiated");
+    truncated_initiated.extend_from_slice(&[0; 16]);
+    assert_eq!(
+        decode_event(&truncated_initiated),
+        Err(EventError::InvalidEvent("Initiated"))
+    );
+
+    let program = PROGRAM_ID.to_string();
+    let logs = vec![
+        invoke(&program, 1),
+        "Program data: not base64!".to_string(),
+        success(&program),
+    ];
+    assert!(matches!(
+        parse_logs(&logs),
+        Err(EventError::InvalidBase64(_))
+    ));
+}
+
+#[test]
+fn decodes_events_from_inner_instructions() {
+    let other_program: Pubkey = OTHER_PROGRAM.parse().unwrap();
+    let transfer = vec![12, 0, 0, 0];
+    let redeem = solana_spl_swaps::instruction::Redeem {
+        secret: vec![10; 20],
+    }
+    .data();
+    let redeemed_event = event_instruction("Redeemed");
+    let initiated_event = event_instruction("Initiated");
+    let inner_instructions = [
+        // Transfers out of the token vault, and the instruction itself when invoked through a CPI
+        (&other_program, transfer.as_slice()),
+        (&PROGRAM_ID, redeem.as_slice()),
+        (&PROGRAM_ID, redeemed_event.as_slice()),
+        // Event instructions are only decoded when invoking this program
+        (&other_program, initiated_event.as_slice()),
+    ];
+
+    let events = parse_inner_instructions(inner_instructions, true).unwrap();
+    assert_eq!(events, vec![SwapEvent::Redeemed(redeemed())]);
+
+    // e.g a transaction whose redeem emitted its event before a later instruction failed
+    let events = parse_inner_instructions(inner_instructions, false).unwrap();
+    assert!(events.is_empty());
+
+    let mut corrupt = event_instruction("Refunded");
+    corrupt.truncate(40);
+    assert_eq!(
+        parse_inner_instructions([(&PROGRAM_ID, corrupt.as_slice())], true),
+        Err(EventError::InvalidEvent("Refunded"))
+    );
+}
@@ -1,12 +1,45 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::hash;
-use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use anchor_lang::solana_program::clock::DEFAULT_MS_PER_SLOT;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use anchor_lang::solana_program::{ed25519_program, hash, keccak};
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+use ripemd::{Digest, Ripemd160};
+use spl_token_2022::extension::permanent_delegate::PermanentDelegate;
+use spl_token_2022::extension::transfer_fee::TransferFeeAmount;
+use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use spl_token_2022::state::AccountState;
 
 declare_id!("2WXpY8havGjfRxme9LUxtjFHTh1EfU3ur4v6wiK4KdNC");
 
 /// The size of Anchor's internal discriminator in a PDA's memory
 const ANCHOR_DISCRIMINATOR: usize = 8;
 
+/// The upper bound on the length of a secret (preimage) that an atomic swap can be initiated with,
+/// ensuring that the `redeem()` transaction carrying it does not exceed the transaction size limit.
+pub const MAX_SECRET_LEN: u16 = 512;
+
+/// The domain separator prefixed to the message of a `SwapOrder` signed for `initiate_with_signature()`
+pub const SWAP_ORDER_DOMAIN: &[u8] = b"solana-spl-swaps:swap-order";
+
+/// The domain separator prefixed to the message of a `RefundIntent` signed for `instant_refund_with_signature()`
+pub const REFUND_INTENT_DOMAIN: &[u8] = b"solana-spl-swaps:refund-intent";
+
+/// The identifier of this chain, as included in the `order_id` of every swap
+pub const CHAIN_ID: &[u8] = b"solana";
+
+/// The sentinel used in place of a mint for atomic swaps of native SOL.
+/// It is stored in `SwapAccount`, used in the `swap_data` seeds and emitted in the events as-is.
+pub const NATIVE_MINT: Pubkey = Pubkey::new_from_array([0; 32]);
+
+/// The upper bound on the protocol fee, in basis points of the escrowed amount (i.e 10%)
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// The number of basis points in a whole
+const BPS_DENOMINATOR: u128 = 10_000;
+
 #[program]
 pub mod solana_spl_swaps {
     use super::*;
@@ -16,9 +49,21 @@ pub mod solana_spl_swaps {
     /// in base units of the token mint.  
     /// E.g: A quantity of $1 represented by the token "USDC" with "6" decimals
     /// must be provided as 1,000,000.  
-    /// `timelock` represents the number of slots after which (non-instant) refunds are allowed.  
+    /// For mints charging a transfer fee (Token-2022 TransferFee extension), the vault receives
+    /// less than `swap_amount`. Only the amount actually received is escrowed for this swap.  
+    /// `timelock` represents the number of slots after which (non-instant) refunds are allowed,
+    /// or the number of seconds or the unix timestamp, as per `timelock_kind`.
+    /// Its duration must lie within the timelock bounds of the configuration or of the mint's policy.  
+    /// `salt` is an arbitrary caller-supplied value (e.g a random number or an order id) that
+    /// distinguishes swaps with otherwise identical parameters.  
+    /// `hash_algorithm` is the hash function with which `secret_hash` was derived from the secret.  
+    /// `max_secret_len` is the maximum length in bytes of the secret accepted upon redeem,
+    /// which can be at most `MAX_SECRET_LEN`.  
+    /// `relayer_tip` is the quantity of tokens paid out of the escrowed amount to the relayer
+    /// submitting the redeem or refund, compensating third parties for completing the swap.  
     /// `destination_data` can hold optional information regarding the destination chain
     /// in the atomic swap, to be emitted in the logs as-is.
+    #[allow(clippy::too_many_arguments)]
     pub fn initiate(
         ctx: Context<Initiate>,
         redeemer: Pubkey,
@@ -26,52 +71,109 @@ pub mod solana_spl_swaps {
         secret_hash: [u8; 32],
         swap_amount: u64, // In base units of the token
         timelock: u64,
+        salt: u64,
+        timelock_kind: TimelockKind,
+        hash_algorithm: HashAlgorithm,
+        max_secret_len: u16,
+        relayer_tip: u64,
         destination_data: Option<Vec<u8>>,
     ) -> Result<()> {
+        hash_algorithm.validate_hashlock(&secret_hash, max_secret_len)?;
+
         let Initiate {
+            config,
             funder,
             funder_token_account,
             mint,
+            mint_policy,
             rent_sponsor,
             token_program,
             token_vault,
+            vault_state,
             ..
         } = ctx.accounts;
+        let clock = Clock::get()?;
+        let config = Config::load(config)?;
+        let mint_policy = MintPolicy::load(mint_policy)?;
+        config.validate_initiation(
+            mint_policy.as_ref(),
+            Some(mint),
+            swap_amount,
+            timelock_kind.duration_secs(timelock, &clock)?,
+        )?;
+        let fee_bps = config.fee_bps_for(mint_policy.as_ref());
 
+        let vault_balance_before = token_vault.amount;
         let token_transfer_context = CpiContext::new(
             token_program.to_account_info(),
-            token::Transfer {
+            token_interface::TransferChecked {
                 from: funder_token_account.to_account_info(),
+                mint: mint.to_account_info(),
                 to: token_vault.to_account_info(),
                 authority: funder.to_account_info(),
             },
         );
-        token::transfer(token_transfer_context, swap_amount)?;
+        token_interface::transfer_checked(token_transfer_context, swap_amount, mint.decimals)?;
+
+        // Measure what the vault actually received, as transfer fees may have been withheld.
+        // Paying out `swap_amount` instead would dip into the funds of other swaps of this mint.
+        let escrowed_amount = deposited_amount(token_vault, vault_balance_before)?;
 
-        let expiry_slot = Clock::get()?
-            .slot
-            .checked_add(timelock)
-            .expect("timelock should not cause an overflow");
+        let expiry = timelock_kind.expiry(timelock, &clock)?;
+        let order_id = compute_order_id(
+            &mint.key(),
+            &redeemer,
+            &refundee,
+            &secret_hash,
+            swap_amount,
+            timelock,
+            salt,
+        );
         *ctx.accounts.swap_data = SwapAccount {
-            expiry_slot,
+            expiry,
+            timelock_kind,
             bump: ctx.bumps.swap_data,
             identity_pda_bump: ctx.bumps.identity_pda,
+            token_vault: token_vault.key(),
+            vault_isolated: false,
             rent_sponsor: rent_sponsor.key(),
             mint: mint.key(),
             redeemer,
+            current_redeemer: redeemer,
             refundee,
+            current_refundee: refundee,
             secret_hash,
+            hash_algorithm,
+            max_secret_len,
             swap_amount,
+            escrowed_amount,
+            fee_bps,
+            fee_recipient: config.fee_recipient,
+            relayer_tip,
             timelock,
+            salt,
+            order_id,
         };
+        ctx.accounts.swap_data.validate_amounts()?;
+        vault_state.bump = ctx.bumps.vault_state;
+        vault_state.mint = mint.key();
+        vault_state.lock(escrowed_amount)?;
 
-        emit!(Initiated {
+        emit_cpi!(Initiated {
             timelock,
+            salt,
+            order_id,
+            timelock_kind,
+            expiry,
             mint: mint.key(),
             redeemer,
             refundee,
             secret_hash,
+            hash_algorithm,
+            max_secret_len,
             swap_amount,
+            escrowed_amount,
+            relayer_tip,
             destination_data,
             funder: ctx.accounts.funder.key(),
         });
@@ -79,225 +181,3009 @@ pub mod solana_spl_swaps {
         Ok(())
     }
 
-    /// Funds are transferred to the redeemer. This instruction does not require any signatures.
-    pub fn redeem(ctx: Context<Redeem>, secret: [u8; 32]) -> Result<()> {
-        let Redeem {
-            identity_pda,
-            redeemer_token_account,
-            swap_data,
+    /// Initiates the atomic swap into a token vault of its own, rather than the token vault shared by
+    /// all swaps of the mint, isolating its funds from those of any other swap.
+    /// The isolated vault is closed upon completion of the swap, refunding its rent to the rent sponsor.  
+    /// The parameters are identical to `initiate()`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initiate_isolated(
+        ctx: Context<InitiateIsolated>,
+        redeemer: Pubkey,
+        refundee: Pubkey,
+        secret_hash: [u8; 32],
+        swap_amount: u64, // In base units of the token
+        timelock: u64,
+        salt: u64,
+        timelock_kind: TimelockKind,
+        hash_algorithm: HashAlgorithm,
+        max_secret_len: u16,
+        relayer_tip: u64,
+        destination_data: Option<Vec<u8>>,
+    ) -> Result<()> {
+        hash_algorithm.validate_hashlock(&secret_hash, max_secret_len)?;
+
+        let InitiateIsolated {
+            config,
+            funder,
+            funder_token_account,
+            mint,
+            mint_policy,
+            rent_sponsor,
             token_program,
             token_vault,
+            vault_state,
             ..
         } = ctx.accounts;
-        let SwapAccount {
-            identity_pda_bump,
-            mint,
-            redeemer,
-            refundee,
-            secret_hash,
+        let clock = Clock::get()?;
+        let config = Config::load(config)?;
+        let mint_policy = MintPolicy::load(mint_policy)?;
+        config.validate_initiation(
+            mint_policy.as_ref(),
+            Some(mint),
             swap_amount,
-            timelock,
-            ..
-        } = **swap_data;
-
-        require!(
-            hash::hash(&secret).to_bytes() == secret_hash,
-            SwapError::InvalidSecret
-        );
+            timelock_kind.duration_secs(timelock, &clock)?,
+        )?;
+        let fee_bps = config.fee_bps_for(mint_policy.as_ref());
 
-        let pda_seeds: &[&[&[u8]]] = &[&[&[identity_pda_bump]]];
         let token_transfer_context = CpiContext::new(
             token_program.to_account_info(),
-            token::Transfer {
-                from: token_vault.to_account_info(),
-                to: redeemer_token_account.to_account_info(),
-                authority: identity_pda.to_account_info(),
+            token_interface::TransferChecked {
+                from: funder_token_account.to_account_info(),
+                mint: mint.to_account_info(),
+                to: token_vault.to_account_info(),
+                authority: funder.to_account_info(),
             },
-        )
-        .with_signer(pda_seeds);
-        token::transfer(token_transfer_context, swap_amount)?;
+        );
+        token_interface::transfer_checked(token_transfer_context, swap_amount, mint.decimals)?;
 
-        emit!(Redeemed {
-            mint,
+        // The vault is new, hence its balance is what it received net of any transfer fee
+        let escrowed_amount = deposited_amount(token_vault, 0)?;
+
+        let expiry = timelock_kind.expiry(timelock, &clock)?;
+        let order_id = compute_order_id(
+            &mint.key(),
+            &redeemer,
+            &refundee,
+            &secret_hash,
+            swap_amount,
+            timelock,
+            salt,
+        );
+        *ctx.accounts.swap_data = SwapAccount {
+            expiry,
+            timelock_kind,
+            bump: ctx.bumps.swap_data,
+            identity_pda_bump: ctx.bumps.identity_pda,
+            token_vault: token_vault.key(),
+            vault_isolated: true,
+            rent_sponsor: rent_sponsor.key(),
+            mint: mint.key(),
             redeemer,
+            current_redeemer: redeemer,
             refundee,
-            secret,
+            current_refundee: refundee,
+            secret_hash,
+            hash_algorithm,
+            max_secret_len,
             swap_amount,
+            escrowed_amount,
+            fee_bps,
+            fee_recipient: config.fee_recipient,
+            relayer_tip,
+            timelock,
+            salt,
+            order_id,
+        };
+        ctx.accounts.swap_data.validate_amounts()?;
+        vault_state.bump = ctx.bumps.vault_state;
+        vault_state.mint = mint.key();
+
+        emit_cpi!(Initiated {
             timelock,
+            salt,
+            order_id,
+            timelock_kind,
+            expiry,
+            mint: mint.key(),
+            redeemer,
+            refundee,
+            secret_hash,
+            hash_algorithm,
+            max_secret_len,
+            swap_amount,
+            escrowed_amount,
+            relayer_tip,
+            destination_data,
+            funder: ctx.accounts.funder.key(),
         });
 
         Ok(())
     }
 
+    /// Funds are transferred to the redeemer, net of the protocol fee, which is transferred
+    /// to the fee recipient's token account, and of the relayer tip, which is transferred
+    /// to the relayer's token account. This instruction does not require any signatures
+    /// other than that of the relayer, i.e the submitter of this transaction.
+    pub fn redeem(ctx: Context<Redeem>, secret: Vec<u8>) -> Result<()> {
+        let Redeem {
+            fee_token_account,
+            identity_pda,
+            mint,
+            redeemer_token_account,
+            relayer_token_account,
+            rent_sponsor,
+            swap_data,
+            token_program,
+            token_vault,
+            vault_state,
+            ..
+        } = ctx.accounts;
+        let vault = SwapVault {
+            swap_data,
+            identity_pda,
+            mint,
+            token_vault,
+            vault_state,
+            rent_sponsor,
+            token_program,
+        };
+        let event = complete_redeem(
+            vault,
+            secret,
+            redeemer_token_account,
+            fee_token_account.as_ref(),
+            relayer_token_account.as_ref(),
+        )?;
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    /// Identical to `redeem()`, except that the associated token account of the redeemer is created
+    /// if it does not exist, with its rent paid by the relayer. The relayer tip of the swap, if any,
+    /// can reimburse this rent. This instruction does not require any signatures other than that of the relayer.
+    pub fn redeem_with_ata(ctx: Context<RedeemWithAta>, secret: Vec<u8>) -> Result<()> {
+        let RedeemWithAta {
+            fee_token_account,
+            identity_pda,
+            mint,
+            redeemer_token_account,
+            relayer_token_account,
+            rent_sponsor,
+            swap_data,
+            token_program,
+            token_vault,
+            vault_state,
+            ..
+        } = ctx.accounts;
+        let vault = SwapVault {
+            swap_data,
+            identity_pda,
+            mint,
+            token_vault,
+            vault_state,
+            rent_sponsor,
+            token_program,
+        };
+        let event = complete_redeem(
+            vault,
+            secret,
+            redeemer_token_account,
+            fee_token_account.as_ref(),
+            relayer_token_account.as_ref(),
+        )?;
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    /// Funds are transferred to a token account of the mint chosen by the redeemer, rather than
+    /// to a token account owned by the redeemer, e.g if theirs is frozen or closed.
+    /// The fees are deducted as with `redeem()`. The redeemer's signature is required for this instruction.
+    pub fn redeem_to(ctx: Context<RedeemTo>, secret: Vec<u8>) -> Result<()> {
+        let RedeemTo {
+            destination_token_account,
+            fee_token_account,
+            identity_pda,
+            mint,
+            relayer_token_account,
+            rent_sponsor,
+            swap_data,
+            token_program,
+            token_vault,
+            vault_state,
+            ..
+        } = ctx.accounts;
+        let vault = SwapVault {
+            swap_data,
+            identity_pda,
+            mint,
+            token_vault,
+            vault_state,
+            rent_sponsor,
+            token_program,
+        };
+        let event = complete_redeem(
+            vault,
+            secret,
+            destination_token_account,
+            fee_token_account.as_ref(),
+            relayer_token_account.as_ref(),
+        )?;
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
     /// Funds are returned to the refundee, given that no redeems have occured
-    /// and the expiry slot has been reached.
-    /// This instruction does not require any signatures.
+    /// and the expiry (slot or unix timestamp) has been reached.
+    /// The relayer tip is deducted and transferred to the relayer's token account.
+    /// This instruction does not require any signatures other than that of the relayer,
+    /// i.e the submitter of this transaction.
     pub fn refund(ctx: Context<Refund>) -> Result<()> {
         let Refund {
             identity_pda,
+            mint,
             refundee_token_account,
+            relayer_token_account,
+            rent_sponsor,
             swap_data,
-            token_vault,
             token_program,
+            token_vault,
+            vault_state,
             ..
         } = ctx.accounts;
-        let SwapAccount {
-            identity_pda_bump,
+        let vault = SwapVault {
+            swap_data,
+            identity_pda,
             mint,
-            redeemer,
-            refundee,
-            expiry_slot,
-            secret_hash,
-            swap_amount,
-            timelock,
-            ..
-        } = **swap_data;
+            token_vault,
+            vault_state,
+            rent_sponsor,
+            token_program,
+        };
+        let event = complete_refund(
+            vault,
+            refundee_token_account,
+            relayer_token_account.as_ref(),
+        )?;
+        emit_cpi!(event);
 
-        require!(
-            Clock::get()?.slot > expiry_slot,
-            SwapError::RefundBeforeExpiry
-        );
+        Ok(())
+    }
 
-        let pda_seeds: &[&[&[u8]]] = &[&[&[identity_pda_bump]]];
-        let token_transfer_context = CpiContext::new(
-            token_program.to_account_info(),
-            token::Transfer {
-                from: token_vault.to_account_info(),
-                to: refundee_token_account.to_account_info(),
-                authority: identity_pda.to_account_info(),
-            },
-        )
-        .with_signer(pda_seeds);
-        token::transfer(token_transfer_context, swap_amount)?;
+    /// Funds are returned to a token account of the mint chosen by the refundee, rather than
+    /// to a token account owned by the refundee, given that the expiry has been reached.
+    /// The relayer tip is deducted as with `refund()`. The refundee's signature is required for this instruction.
+    pub fn refund_to(ctx: Context<RefundTo>) -> Result<()> {
+        let RefundTo {
+            destination_token_account,
+            identity_pda,
+            mint,
+            relayer_token_account,
+            rent_sponsor,
+            swap_data,
+            token_program,
+            token_vault,
+            vault_state,
+            ..
+        } = ctx.accounts;
+        let vault = SwapVault {
+            swap_data,
+            identity_pda,
+            mint,
+            token_vault,
+            vault_state,
+            rent_sponsor,
+            token_program,
+        };
+        let event = complete_refund(
+            vault,
+            destination_token_account,
+            relayer_token_account.as_ref(),
+        )?;
+        emit_cpi!(event);
 
-        emit!(Refunded {
+        Ok(())
+    }
+
+    /// Identical to `refund()`, except that the associated token account of the refundee is created
+    /// if it does not exist, with its rent paid by the relayer. The relayer tip of the swap, if any,
+    /// can reimburse this rent. This instruction does not require any signatures other than that of the relayer.
+    pub fn refund_with_ata(ctx: Context<RefundWithAta>) -> Result<()> {
+        let RefundWithAta {
+            identity_pda,
             mint,
-            redeemer,
-            refundee,
-            secret_hash,
-            swap_amount,
-            timelock,
-        });
+            refundee_token_account,
+            relayer_token_account,
+            rent_sponsor,
+            swap_data,
+            token_program,
+            token_vault,
+            vault_state,
+            ..
+        } = ctx.accounts;
+        let vault = SwapVault {
+            swap_data,
+            identity_pda,
+            mint,
+            token_vault,
+            vault_state,
+            rent_sponsor,
+            token_program,
+        };
+        let event = complete_refund(
+            vault,
+            refundee_token_account,
+            relayer_token_account.as_ref(),
+        )?;
+        emit_cpi!(event);
 
         Ok(())
     }
 
     /// Funds are returned to the refundee, with the redeemer's consent.
     /// As such, the redeemer's signature is required for this instruction.
-    /// This allows for refunds before the expiry slot.
+    /// This allows for refunds before the expiry.
     pub fn instant_refund(ctx: Context<InstantRefund>) -> Result<()> {
         let InstantRefund {
             identity_pda,
+            mint,
             refundee_token_account,
+            rent_sponsor,
             swap_data,
             token_program,
             token_vault,
+            vault_state,
             ..
         } = ctx.accounts;
-        let SwapAccount {
-            identity_pda_bump,
+        let vault = SwapVault {
+            swap_data,
+            identity_pda,
             mint,
-            redeemer,
-            refundee,
-            secret_hash,
-            swap_amount,
-            timelock,
-            ..
-        } = **swap_data;
+            token_vault,
+            vault_state,
+            rent_sponsor,
+            token_program,
+        };
+        let event = complete_instant_refund(vault, refundee_token_account)?;
+        emit_cpi!(event);
 
-        let pda_seeds: &[&[&[u8]]] = &[&[&[identity_pda_bump]]];
-        let token_transfer_context = CpiContext::new(
-            token_program.to_account_info(),
-            token::Transfer {
-                from: token_vault.to_account_info(),
-                to: refundee_token_account.to_account_info(),
-                authority: identity_pda.to_account_info(),
-            },
-        )
-        .with_signer(pda_seeds);
-        token::transfer(token_transfer_context, swap_amount)?;
+        Ok(())
+    }
 
-        emit!(InstantRefunded {
+    /// Identical to `instant_refund()`, except that the associated token account of the refundee
+    /// is created if it does not exist, with its rent paid by the relayer.
+    /// As such, the redeemer's signature is required for this instruction.
+    pub fn instant_refund_with_ata(ctx: Context<InstantRefundWithAta>) -> Result<()> {
+        let InstantRefundWithAta {
+            identity_pda,
             mint,
-            redeemer,
-            refundee,
-            secret_hash,
-            swap_amount,
-            timelock,
-        });
+            refundee_token_account,
+            rent_sponsor,
+            swap_data,
+            token_program,
+            token_vault,
+            vault_state,
+            ..
+        } = ctx.accounts;
+        let vault = SwapVault {
+            swap_data,
+            identity_pda,
+            mint,
+            token_vault,
+            vault_state,
+            rent_sponsor,
+            token_program,
+        };
+        let event = complete_instant_refund(vault, refundee_token_account)?;
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    /// Initiates an atomic swap of native SOL. Lamports are transferred from the funder
+    /// to the `swap_data` PDA, which escrows them directly without any token wrapping.
+    /// `swap_amount` is in lamports. The remaining parameters are identical to `initiate()`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initiate_native(
+        ctx: Context<InitiateNative>,
+        redeemer: Pubkey,
+        refundee: Pubkey,
+        secret_hash: [u8; 32],
+        swap_amount: u64, // In lamports
+        timelock: u64,
+        salt: u64,
+        timelock_kind: TimelockKind,
+        hash_algorithm: HashAlgorithm,
+        max_secret_len: u16,
+        relayer_tip: u64,
+        destination_data: Option<Vec<u8>>,
+    ) -> Result<()> {
+        hash_algorithm.validate_hashlock(&secret_hash, max_secret_len)?;
+
+        let InitiateNative {
+            config,
+            funder,
+            mint_policy,
+            rent_sponsor,
+            swap_data,
+            system_program,
+            ..
+        } = ctx.accounts;
+        let clock = Clock::get()?;
+        let config = Config::load(config)?;
+        let mint_policy = MintPolicy::load(mint_policy)?;
+        config.validate_initiation(
+            mint_policy.as_ref(),
+            None,
+            swap_amount,
+            timelock_kind.duration_secs(timelock, &clock)?,
+        )?;
+        let fee_bps = config.fee_bps_for(mint_policy.as_ref());
+
+        let lamport_transfer_context = CpiContext::new(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: funder.to_account_info(),
+                to: swap_data.to_account_info(),
+            },
+        );
+        system_program::transfer(lamport_transfer_context, swap_amount)?;
+
+        let expiry = timelock_kind.expiry(timelock, &clock)?;
+        let order_id = compute_order_id(
+            &NATIVE_MINT,
+            &redeemer,
+            &refundee,
+            &secret_hash,
+            swap_amount,
+            timelock,
+            salt,
+        );
+        **swap_data = SwapAccount {
+            expiry,
+            timelock_kind,
+            bump: ctx.bumps.swap_data,
+            // Native swaps do not involve the token vault and hence its authority
+            identity_pda_bump: 0,
+            token_vault: Pubkey::default(),
+            vault_isolated: false,
+            rent_sponsor: rent_sponsor.key(),
+            mint: NATIVE_MINT,
+            redeemer,
+            current_redeemer: redeemer,
+            refundee,
+            current_refundee: refundee,
+            secret_hash,
+            hash_algorithm,
+            max_secret_len,
+            swap_amount,
+            escrowed_amount: swap_amount,
+            fee_bps,
+            fee_recipient: config.fee_recipient,
+            relayer_tip,
+            timelock,
+            salt,
+            order_id,
+        };
+        swap_data.validate_amounts()?;
+        // The redeemer may hold no lamports yet, in which case its payout must make it rent-exempt
+        require!(
+            Rent::get()?.is_exempt(swap_amount - swap_data.protocol_fee()? - relayer_tip, 0),
+            SwapError::PayoutNotRentExempt
+        );
+
+        emit_cpi!(Initiated {
+            timelock,
+            salt,
+            order_id,
+            timelock_kind,
+            expiry,
+            mint: NATIVE_MINT,
+            redeemer,
+            refundee,
+            secret_hash,
+            hash_algorithm,
+            max_secret_len,
+            swap_amount,
+            escrowed_amount: swap_amount,
+            relayer_tip,
+            destination_data,
+            funder: funder.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Escrowed lamports are transferred to the redeemer, net of the protocol fee, which is transferred
+    /// to the fee recipient, and of the relayer tip, which is transferred to the relayer.
+    /// A protocol fee that would leave the fee recipient below the rent-exempt minimum is waived.
+    /// This instruction does not require any signatures other than that of the relayer.
+    pub fn redeem_native(ctx: Context<RedeemNative>, secret: Vec<u8>) -> Result<()> {
+        let RedeemNative {
+            fee_recipient,
+            redeemer: redeemer_account,
+            relayer,
+            swap_data,
+            ..
+        } = ctx.accounts;
+        let SwapAccount {
+            mint,
+            redeemer,
+            refundee,
+            swap_amount,
+            escrowed_amount,
+            relayer_tip,
+            timelock,
+            salt,
+            order_id,
+            ..
+        } = **swap_data;
+
+        swap_data.verify_secret(&secret)?;
+
+        // A fee that the fee recipient can not receive is waived rather than failing the redeem,
+        // as the secret is revealed by the redeem transaction regardless of its outcome
+        let rent = Rent::get()?;
+        let mut fee_amount = swap_data.protocol_fee()?;
+        if fee_amount > 0 {
+            let fee_recipient = fee_recipient
+                .as_ref()
+                .ok_or(SwapError::FeeAccountRequired)?;
+            if can_receive_lamports(fee_recipient, fee_amount, &rent) {
+                fee_recipient.add_lamports(fee_amount)?;
+            } else {
+                fee_amount = 0;
+            }
+        }
+        let payout = escrowed_amount - fee_amount - relayer_tip;
+        require!(
+            can_receive_lamports(redeemer_account, payout, &rent),
+            SwapError::PayoutNotRentExempt
+        );
+        swap_data.sub_lamports(escrowed_amount)?;
+        relayer.add_lamports(relayer_tip)?;
+        redeemer_account.add_lamports(payout)?;
+
+        emit_cpi!(Redeemed {
+            mint,
+            redeemer,
+            refundee,
+            secret,
+            swap_amount,
+            fee_amount,
+            relayer_tip,
+            timelock,
+            salt,
+            order_id,
+            current_redeemer: redeemer_account.key(),
+            destination: redeemer_account.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Escrowed lamports are returned to the refundee, given that no redeems have occured
+    /// and the expiry (slot or unix timestamp) has been reached.
+    /// The relayer tip is deducted and transferred to the relayer.
+    /// This instruction does not require any signatures other than that of the relayer.
+    pub fn refund_native(ctx: Context<RefundNative>) -> Result<()> {
+        let RefundNative {
+            refundee: refundee_account,
+            relayer,
+            swap_data,
+            ..
+        } = ctx.accounts;
+        let SwapAccount {
+            mint,
+            redeemer,
+            refundee,
+            expiry,
+            secret_hash,
+            swap_amount,
+            escrowed_amount,
+            relayer_tip,
+            timelock,
+            salt,
+            order_id,
+            ..
+        } = **swap_data;
+
+        require!(
+            swap_data.has_expired(&Clock::get()?)?,
+            SwapError::RefundBeforeExpiry
+        );
+
+        swap_data.sub_lamports(escrowed_amount)?;
+        relayer.add_lamports(relayer_tip)?;
+        refundee_account.add_lamports(escrowed_amount - relayer_tip)?;
+
+        emit_cpi!(Refunded {
+            mint,
+            redeemer,
+            refundee,
+            secret_hash,
+            swap_amount,
+            relayer_tip,
+            timelock,
+            salt,
+            order_id,
+            expiry,
+            current_refundee: refundee_account.key(),
+            destination: refundee_account.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Escrowed lamports are returned to the refundee, with the redeemer's consent.
+    /// As such, the redeemer's signature is required for this instruction.
+    /// This allows for refunds before the expiry.
+    pub fn instant_refund_native(ctx: Context<InstantRefundNative>) -> Result<()> {
+        let InstantRefundNative {
+            refundee: refundee_account,
+            swap_data,
+            ..
+        } = ctx.accounts;
+        let SwapAccount {
+            mint,
+            redeemer,
+            refundee,
+            secret_hash,
+            swap_amount,
+            escrowed_amount,
+            timelock,
+            salt,
+            order_id,
+            ..
+        } = **swap_data;
+
+        swap_data.sub_lamports(escrowed_amount)?;
+        refundee_account.add_lamports(escrowed_amount)?;
+
+        emit_cpi!(InstantRefunded {
+            mint,
+            redeemer,
+            refundee,
+            secret_hash,
+            swap_amount,
+            timelock,
+            salt,
+            order_id,
+            current_refundee: refundee_account.key(),
+            destination: refundee_account.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Initiates the atomic swap on behalf of the owner of the funds, as authorized by their
+    /// Ed25519 signature over `order`. This allows a relayer to submit initiations without holding
+    /// the owner's keys, in which case the relayer pays for the transaction and the PDA rent.  
+    /// The Ed25519 program instruction verifying the signature must immediately precede this instruction.
+    /// The signed message is `SWAP_ORDER_DOMAIN || program id || order` (Borsh serialized).  
+    /// Funds are pulled from the owner's token account via a delegate approval granted to the `identity_pda`.  
+    /// `order.nonce` must be the owner's next nonce, as held by the `signer_nonce` PDA,
+    /// and the transaction must land by `order.deadline`.  
+    /// `destination_data` is emitted in the logs as-is and is not covered by the signature.
+    pub fn initiate_with_signature(
+        ctx: Context<InitiateWithSignature>,
+        order: SwapOrder,
+        destination_data: Option<Vec<u8>>,
+    ) -> Result<()> {
+        let InitiateWithSignature {
+            config,
+            identity_pda,
+            instructions,
+            mint,
+            mint_policy,
+            owner,
+            owner_token_account,
+            rent_sponsor,
+            signer_nonce,
+            token_program,
+            token_vault,
+            vault_state,
+            ..
+        } = ctx.accounts;
+        let SwapOrder {
+            redeemer,
+            refundee,
+            secret_hash,
+            swap_amount,
+            timelock,
+            timelock_kind,
+            hash_algorithm,
+            salt,
+            max_secret_len,
+            relayer_tip,
+            nonce,
+            deadline,
+            ..
+        } = order;
+
+        let message = [SWAP_ORDER_DOMAIN, crate::ID.as_ref(), &order.try_to_vec()?].concat();
+        verify_ed25519_signature(instructions, &owner.key(), &message)?;
+        let clock = Clock::get()?;
+        require!(
+            clock.unix_timestamp <= deadline,
+            SwapError::SignatureExpired
+        );
+        require!(nonce == signer_nonce.next_nonce, SwapError::InvalidNonce);
+        signer_nonce.bump = ctx.bumps.signer_nonce;
+        signer_nonce.next_nonce = nonce.checked_add(1).ok_or(SwapError::InvalidNonce)?;
+
+        hash_algorithm.validate_hashlock(&secret_hash, max_secret_len)?;
+        let config = Config::load(config)?;
+        let mint_policy = MintPolicy::load(mint_policy)?;
+        config.validate_initiation(
+            mint_policy.as_ref(),
+            Some(mint),
+            swap_amount,
+            timelock_kind.duration_secs(timelock, &clock)?,
+        )?;
+        let fee_bps = config.fee_bps_for(mint_policy.as_ref());
+
+        let vault_balance_before = token_vault.amount;
+        let pda_seeds: &[&[&[u8]]] = &[&[&[ctx.bumps.identity_pda]]];
+        let token_transfer_context = CpiContext::new(
+            token_program.to_account_info(),
+            token_interface::TransferChecked {
+                from: owner_token_account.to_account_info(),
+                mint: mint.to_account_info(),
+                to: token_vault.to_account_info(),
+                authority: identity_pda.to_account_info(),
+            },
+        )
+        .with_signer(pda_seeds);
+        token_interface::transfer_checked(token_transfer_context, swap_amount, mint.decimals)?;
+
+        // Measure what the vault actually received, as transfer fees may have been withheld
+        let escrowed_amount = deposited_amount(token_vault, vault_balance_before)?;
+
+        let expiry = timelock_kind.expiry(timelock, &clock)?;
+        let order_id = compute_order_id(
+            &mint.key(),
+            &redeemer,
+            &refundee,
+            &secret_hash,
+            swap_amount,
+            timelock,
+            salt,
+        );
+        *ctx.accounts.swap_data = SwapAccount {
+            expiry,
+            timelock_kind,
+            bump: ctx.bumps.swap_data,
+            identity_pda_bump: ctx.bumps.identity_pda,
+            token_vault: token_vault.key(),
+            vault_isolated: false,
+            rent_sponsor: rent_sponsor.key(),
+            mint: mint.key(),
+            redeemer,
+            current_redeemer: redeemer,
+            refundee,
+            current_refundee: refundee,
+            secret_hash,
+            hash_algorithm,
+            max_secret_len,
+            swap_amount,
+            escrowed_amount,
+            fee_bps,
+            fee_recipient: config.fee_recipient,
+            relayer_tip,
+            timelock,
+            salt,
+            order_id,
+        };
+        ctx.accounts.swap_data.validate_amounts()?;
+        vault_state.bump = ctx.bumps.vault_state;
+        vault_state.mint = mint.key();
+        vault_state.lock(escrowed_amount)?;
+
+        emit_cpi!(Initiated {
+            timelock,
+            salt,
+            order_id,
+            timelock_kind,
+            expiry,
+            mint: mint.key(),
+            redeemer,
+            refundee,
+            secret_hash,
+            hash_algorithm,
+            max_secret_len,
+            swap_amount,
+            escrowed_amount,
+            relayer_tip,
+            destination_data,
+            funder: ctx.accounts.owner.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Funds are returned to the refundee, with the redeemer's consent as given by their
+    /// Ed25519 signature over the `RefundIntent` of this swap.
+    /// As such, anyone (e.g the refundee or a relayer) can submit this instruction.
+    /// The Ed25519 program instruction verifying the signature must immediately precede this instruction.
+    /// The signed message is `REFUND_INTENT_DOMAIN || program id || intent` (Borsh serialized).
+    /// `intent.nonce` must be the redeemer's next nonce, as held by the `signer_nonce` PDA.  
+    /// This allows for refunds before the expiry.
+    pub fn instant_refund_with_signature(ctx: Context<InstantRefundWithSignature>) -> Result<()> {
+        let InstantRefundWithSignature {
+            identity_pda,
+            instructions,
+            mint,
+            refundee_token_account,
+            rent_sponsor,
+            signer_nonce,
+            swap_data,
+            token_program,
+            token_vault,
+            vault_state,
+            ..
+        } = ctx.accounts;
+
+        let intent = RefundIntent {
+            swap_data: swap_data.key(),
+            nonce: signer_nonce.next_nonce,
+        };
+        let message = [
+            REFUND_INTENT_DOMAIN,
+            crate::ID.as_ref(),
+            &intent.try_to_vec()?,
+        ]
+        .concat();
+        verify_ed25519_signature(instructions, &swap_data.current_redeemer, &message)?;
+        signer_nonce.bump = ctx.bumps.signer_nonce;
+        signer_nonce.next_nonce = intent.nonce.checked_add(1).ok_or(SwapError::InvalidNonce)?;
+
+        let vault = SwapVault {
+            swap_data,
+            identity_pda,
+            mint,
+            token_vault,
+            vault_state,
+            rent_sponsor,
+            token_program,
+        };
+        let event = complete_instant_refund(vault, refundee_token_account)?;
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    /// Rotates the party to which the funds of the swap are returned upon refund, e.g if the refundee
+    /// has lost access to their key. The current refundee's signature is required for this instruction.
+    pub fn update_refundee(ctx: Context<UpdateRefundee>, new_refundee: Pubkey) -> Result<()> {
+        let swap_data = &mut ctx.accounts.swap_data;
+        let previous_refundee = swap_data.current_refundee;
+        swap_data.current_refundee = new_refundee;
+
+        emit_cpi!(RefundeeUpdated {
+            order_id: swap_data.order_id,
+            previous_refundee,
+            new_refundee,
+        });
+
+        Ok(())
+    }
+
+    /// Reassigns the beneficiary of the swap, e.g to hand an open swap over to another solver key.
+    /// The new redeemer is paid upon redeem and must consent to instant refunds, whereas the PDA seeds
+    /// remain derived from the original redeemer. The current redeemer's signature is required for this instruction.
+    pub fn transfer_redeemer(ctx: Context<TransferRedeemer>, new_redeemer: Pubkey) -> Result<()> {
+        let swap_data = &mut ctx.accounts.swap_data;
+        let previous_redeemer = swap_data.current_redeemer;
+        swap_data.current_redeemer = new_redeemer;
+
+        emit_cpi!(RedeemerTransferred {
+            order_id: swap_data.order_id,
+            previous_redeemer,
+            new_redeemer,
+        });
+
+        Ok(())
+    }
+
+    /// Creates the program configuration. Only the upgrade authority of this program can do so,
+    /// which prevents anyone from claiming the admin role upon deployment.
+    pub fn initialize_config(ctx: Context<InitializeConfig>, params: ConfigParams) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.bump = ctx.bumps.config;
+        config.paused = false;
+        config.set_params(params)
+    }
+
+    /// Updates the program configuration. The admin's signature is required for this instruction.  
+    /// The fee of a swap is determined upon its initiation, hence swaps initiated prior to this update
+    /// are unaffected by a change of `fee_bps`.
+    pub fn update_config(ctx: Context<UpdateConfig>, params: ConfigParams) -> Result<()> {
+        ctx.accounts.config.set_params(params)
+    }
+
+    /// Sets the policy for swaps of `mint`, or of native SOL if `mint` is `NATIVE_MINT`.
+    /// The admin's signature is required for this instruction.  
+    /// The policy is checked upon initiation, hence swaps initiated prior to this update are unaffected.
+    pub fn set_mint_policy(
+        ctx: Context<SetMintPolicy>,
+        mint: Pubkey,
+        params: MintPolicyParams,
+    ) -> Result<()> {
+        let mint_policy = &mut ctx.accounts.mint_policy;
+        mint_policy.bump = ctx.bumps.mint_policy;
+        mint_policy.mint = mint;
+        mint_policy.set_params(params)
+    }
+
+    /// Pauses or resumes initiations for all mints. The guardian's signature is required for this instruction.  
+    /// Redeems and refunds are never paused, such that the funds of open swaps can always exit.
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        ctx.accounts.config.paused = paused;
+
+        Ok(())
+    }
+
+    /// Pauses or resumes initiations for `mint`, or for native SOL if `mint` is `NATIVE_MINT`.
+    /// The guardian's signature is required for this instruction.  
+    /// Redeems and refunds are never paused, such that the funds of open swaps can always exit.
+    pub fn set_mint_paused(ctx: Context<SetMintPaused>, mint: Pubkey, paused: bool) -> Result<()> {
+        let mint_policy = &mut ctx.accounts.mint_policy;
+        mint_policy.bump = ctx.bumps.mint_policy;
+        mint_policy.mint = mint;
+        mint_policy.paused = paused;
+
+        Ok(())
+    }
+
+    /// Compares the balance of the shared token vault of a mint against the tokens owed to its open swaps,
+    /// emitting the surplus or deficit. This instruction is permissionless.
+    pub fn reconcile(ctx: Context<Reconcile>) -> Result<()> {
+        let vault_state = &ctx.accounts.vault_state;
+        let vault_balance = ctx.accounts.token_vault.amount;
+
+        emit!(Reconciled {
+            mint: vault_state.mint,
+            vault_balance,
+            total_locked: vault_state.total_locked,
+            open_swaps: vault_state.open_swaps,
+            surplus: vault_balance.saturating_sub(vault_state.total_locked),
+            deficit: vault_state.total_locked.saturating_sub(vault_balance),
+        });
+
+        Ok(())
+    }
+
+    /// Transfers the tokens of the shared token vault of a mint in excess of those owed to its open swaps,
+    /// e.g sent to the vault by mistake, to `destination_token_account`.
+    /// The admin's signature is required for this instruction.
+    pub fn sweep_surplus(ctx: Context<SweepSurplus>) -> Result<()> {
+        let SweepSurplus {
+            identity_pda,
+            vault_state,
+            token_vault,
+            mint,
+            destination_token_account,
+            token_program,
+            ..
+        } = ctx.accounts;
+
+        let surplus = token_vault.amount.saturating_sub(vault_state.total_locked);
+        require!(surplus > 0, SwapError::NoSurplus);
+
+        transfer_from_vault(
+            token_program,
+            token_vault,
+            mint,
+            &destination_token_account.to_account_info(),
+            identity_pda,
+            ctx.bumps.identity_pda,
+            surplus,
+        )?;
+
+        emit!(SurplusSwept {
+            mint: mint.key(),
+            destination_token_account: destination_token_account.key(),
+            amount: surplus,
+        });
+
+        Ok(())
+    }
+}
+
+/// Stores the state information of the atomic swap on-chain
+#[account]
+#[derive(InitSpace)]
+pub struct SwapAccount {
+    /// The bump that derived this PDA.
+    /// Storing this makes later verifications less expensive.
+    pub bump: u8,
+    /// The exact slot or unix timestamp, as per `timelock_kind`, after which (non-instant) refunds are allowed
+    pub expiry: u64,
+    /// Whether `timelock` and `expiry` are expressed in slots or in seconds
+    pub timelock_kind: TimelockKind,
+    /// The bump associated with the identity pda.
+    /// This is needed by the program to authorize token transfers via the token vault.
+    pub identity_pda_bump: u8,
+    /// The token vault escrowing the tokens of this swap, or the default pubkey for atomic swaps of native SOL
+    pub token_vault: Pubkey,
+    /// Whether `token_vault` is dedicated to this swap, as per `initiate_isolated()`,
+    /// in which case it is closed upon completion of the swap
+    pub vault_isolated: bool,
+    /// The entity that paid the rent fees for the creation of this PDA.
+    /// This will be referenced during the refund of the same upon closing this PDA.
+    pub rent_sponsor: Pubkey,
+
+    /// The mint for this atomic swap, or `NATIVE_MINT` for atomic swaps of native SOL
+    pub mint: Pubkey,
+    /// The redeemer of the atomic swap, as per the PDA seeds
+    pub redeemer: Pubkey,
+    /// The party to which the funds are paid upon redeem, and whose consent an instant refund requires.
+    /// It is initially `redeemer` and can be reassigned by itself through `transfer_redeemer()`.
+    pub current_redeemer: Pubkey,
+    /// The refundee of the atomic swap, as per the PDA seeds
+    pub refundee: Pubkey,
+    /// The party to which the funds are returned upon refund, initially `refundee`.
+    /// It can be rotated by itself through `update_refundee()`.
+    pub current_refundee: Pubkey,
+    /// The secret hash associated with the atomic swap
+    pub secret_hash: [u8; 32],
+    /// The hash function with which `secret_hash` was derived from the secret
+    pub hash_algorithm: HashAlgorithm,
+    /// The maximum length in bytes of the secret accepted upon redeem
+    pub max_secret_len: u16,
+    /// The quantity tokens to be transferred through this atomic swap
+    /// in base units of the token mint.  
+    /// E.g: A quantity of $1 represented by the token "USDC" with "6" decimals
+    /// must be provided as 1,000,000.
+    pub swap_amount: u64,
+    /// The quantity of tokens actually received by the token vault for this swap, i.e `swap_amount`
+    /// net of any transfer fee withheld by the mint. This is the amount paid out upon redeem or refund.
+    pub escrowed_amount: u64,
+    /// The protocol fee charged upon redeem in basis points of `escrowed_amount`, as of the initiation
+    pub fee_bps: u16,
+    /// The owner of the account to which the protocol fee is paid upon redeem, as of the initiation
+    pub fee_recipient: Pubkey,
+    /// The quantity of tokens paid out of `escrowed_amount` to the relayer submitting the redeem or refund
+    pub relayer_tip: u64,
+    /// Represents the number of slots or seconds after which (non-instant) refunds are allowed,
+    /// or the unix timestamp itself, as per `timelock_kind`
+    pub timelock: u64,
+    /// The caller-supplied value distinguishing swaps with otherwise identical parameters
+    pub salt: u64,
+    /// The canonical identifier of this swap across chains, as computed by `compute_order_id()`
+    pub order_id: [u8; 32],
+}
+
+impl SwapAccount {
+    /// Ensures that the given secret is within the permitted length
+    /// and hashes to the secret hash of this swap
+    fn verify_secret(&self, secret: &[u8]) -> Result<()> {
+        require!(
+            secret.len() <= usize::from(self.max_secret_len),
+            SwapError::SecretTooLong
+        );
+        require!(
+            self.hash_algorithm.digest(secret) == self.secret_hash,
+            SwapError::InvalidSecret
+        );
+        Ok(())
+    }
+
+    /// Whether the expiry of this swap has passed, beyond which (non-instant) refunds are allowed
+    fn has_expired(&self, clock: &Clock) -> Result<bool> {
+        Ok(self.timelock_kind.now(clock)? > self.expiry)
+    }
+
+    /// Ensures that the escrowed amount is non-zero and covers both the protocol fee and the relayer tip
+    fn validate_amounts(&self) -> Result<()> {
+        require!(self.escrowed_amount > 0, SwapError::ZeroSwapAmount);
+        require!(
+            self.relayer_tip <= self.escrowed_amount - self.protocol_fee()?,
+            SwapError::RelayerTipTooLarge
+        );
+        Ok(())
+    }
+
+    /// The protocol fee deducted from the escrowed amount upon redeem
+    fn protocol_fee(&self) -> Result<u64> {
+        let fee = u128::from(self.escrowed_amount) * u128::from(self.fee_bps) / BPS_DENOMINATOR;
+        u64::try_from(fee)
+            .ok()
+            .filter(|&fee| fee <= self.escrowed_amount)
+            .ok_or(error!(SwapError::InvalidFeeBps))
+    }
+}
+
+/// The program-wide configuration, of which there is a single instance
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    /// The bump that derived this PDA
+    pub bump: u8,
+    /// The entity allowed to update this configuration
+    pub admin: Pubkey,
+    /// The entity allowed to pause initiations, globally or per mint
+    pub guardian: Pubkey,
+    /// Whether initiations are paused for all mints
+    pub paused: bool,
+    /// The owner of the accounts to which protocol fees are paid
+    pub fee_recipient: Pubkey,
+    /// The protocol fee charged upon redeem in basis points of the escrowed amount,
+    /// unless overridden for the mint of the swap
+    pub fee_bps: u16,
+    /// Whether only mints whose policy has `allowed` set can be swapped
+    pub allowlist_enabled: bool,
+    /// The minimum duration of the timelock of a swap in seconds, unless overridden for its mint
+    pub min_timelock: u64,
+    /// The maximum duration of the timelock of a swap in seconds, unless overridden for its mint
+    pub max_timelock: u64,
+}
+
+/// The admin-controlled fields of `Config`, as set by `initialize_config()` and `update_config()`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConfigParams {
+    pub admin: Pubkey,
+    pub guardian: Pubkey,
+    pub fee_recipient: Pubkey,
+    /// Can be at most `MAX_FEE_BPS`
+    pub fee_bps: u16,
+    pub allowlist_enabled: bool,
+    /// Can be at most `max_timelock`
+    pub min_timelock: u64,
+    pub max_timelock: u64,
+}
+
+impl Config {
+    /// Loads the configuration from its PDA, falling back to a configuration without fees
+    /// nor restrictions on initiations if it has not been created
+    fn load(config: &AccountInfo) -> Result<Config> {
+        if config.owner != &crate::ID {
+            return Ok(Config {
+                bump: 0,
+                admin: Pubkey::default(),
+                guardian: Pubkey::default(),
+                paused: false,
+                fee_recipient: Pubkey::default(),
+                fee_bps: 0,
+                allowlist_enabled: false,
+                min_timelock: 0,
+                max_timelock: u64::MAX,
+            });
+        }
+        let data = config.try_borrow_data()?;
+        Config::try_deserialize(&mut &data[..])
+    }
+
+    /// Validates and applies the admin-controlled fields of this configuration
+    fn set_params(&mut self, params: ConfigParams) -> Result<()> {
+        let ConfigParams {
+            admin,
+            guardian,
+            fee_recipient,
+            fee_bps,
+            allowlist_enabled,
+            min_timelock,
+            max_timelock,
+        } = params;
+        require!(fee_bps <= MAX_FEE_BPS, SwapError::InvalidFeeBps);
+        require!(
+            min_timelock <= max_timelock,
+            SwapError::InvalidTimelockBounds
+        );
+
+        self.admin = admin;
+        self.guardian = guardian;
+        self.fee_recipient = fee_recipient;
+        self.fee_bps = fee_bps;
+        self.allowlist_enabled = allowlist_enabled;
+        self.min_timelock = min_timelock;
+        self.max_timelock = max_timelock;
+        Ok(())
+    }
+
+    /// Ensures that a new swap is permitted by this configuration and by `mint_policy`,
+    /// the policy of its mint if any. `mint` is `None` for atomic swaps of native SOL.
+    /// `timelock_secs` is the duration of the timelock, as per `TimelockKind::duration_secs()`.
+    fn validate_initiation(
+        &self,
+        mint_policy: Option<&MintPolicy>,
+        mint: Option<&InterfaceAccount<Mint>>,
+        swap_amount: u64,
+        timelock_secs: u64,
+    ) -> Result<()> {
+        require!(!self.paused, SwapError::Paused);
+        require!(swap_amount > 0, SwapError::ZeroSwapAmount);
+
+        let min_timelock = mint_policy
+            .and_then(|policy| policy.min_timelock)
+            .unwrap_or(self.min_timelock);
+        let max_timelock = mint_policy
+            .and_then(|policy| policy.max_timelock)
+            .unwrap_or(self.max_timelock);
+        require!(
+            timelock_secs > 0 && timelock_secs >= min_timelock,
+            SwapError::TimelockTooShort
+        );
+        require!(timelock_secs <= max_timelock, SwapError::TimelockTooLong);
+
+        require!(
+            !self.allowlist_enabled || mint_policy.is_some_and(|policy| policy.allowed),
+            SwapError::MintNotAllowed
+        );
+        let Some(policy) = mint_policy else {
+            return Ok(());
+        };
+        require!(!policy.paused, SwapError::MintPaused);
+
+        if let Some(mint) = mint {
+            require!(
+                !(policy.reject_freeze_authority && mint.freeze_authority.is_some()),
+                SwapError::FreezeAuthorityNotAllowed
+            );
+            require!(
+                !(policy.reject_permanent_delegate && has_permanent_delegate(mint)?),
+                SwapError::PermanentDelegateNotAllowed
+            );
+        }
+
+        require!(
+            swap_amount >= policy.min_swap_amount.unwrap_or(u64::MIN),
+            SwapError::SwapAmountTooSmall
+        );
+        require!(
+            swap_amount <= policy.max_swap_amount.unwrap_or(u64::MAX),
+            SwapError::SwapAmountTooLarge
+        );
+        Ok(())
+    }
+
+    /// The protocol fee applicable to new swaps of the mint governed by `mint_policy`
+    fn fee_bps_for(&self, mint_policy: Option<&MintPolicy>) -> u16 {
+        mint_policy
+            .and_then(|policy| policy.fee_bps)
+            .unwrap_or(self.fee_bps)
+    }
+}
+
+/// The configuration specific to a mint, overriding the program-wide configuration
+#[account]
+#[derive(InitSpace)]
+pub struct MintPolicy {
+    /// The bump that derived this PDA
+    pub bump: u8,
+    /// The mint this policy applies to, or `NATIVE_MINT` for atomic swaps of native SOL
+    pub mint: Pubkey,
+    /// Whether initiations are paused for this mint
+    pub paused: bool,
+    /// The protocol fee in basis points overriding that of `Config`, if any
+    pub fee_bps: Option<u16>,
+    /// Whether this mint can be swapped when `Config::allowlist_enabled` is set
+    pub allowed: bool,
+    /// The minimum `swap_amount` of a swap of this mint, if any
+    pub min_swap_amount: Option<u64>,
+    /// The maximum `swap_amount` of a swap of this mint, if any
+    pub max_swap_amount: Option<u64>,
+    /// The minimum duration of the timelock of a swap of this mint in seconds,
+    /// overriding that of `Config`, if any
+    pub min_timelock: Option<u64>,
+    /// The maximum duration of the timelock of a swap of this mint in seconds,
+    /// overriding that of `Config`, if any
+    pub max_timelock: Option<u64>,
+    /// Whether to reject this mint if it has a freeze authority, which could freeze the token vault
+    pub reject_freeze_authority: bool,
+    /// Whether to reject this mint if it has a permanent delegate (Token-2022),
+    /// which could transfer the escrowed tokens out of the token vault
+    pub reject_permanent_delegate: bool,
+}
+
+/// The admin-controlled fields of `MintPolicy`, as set by `set_mint_policy()`.
+/// A policy created by the guardian through `set_mint_paused()` holds the zero value of every field.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MintPolicyParams {
+    /// Can be at most `MAX_FEE_BPS`
+    pub fee_bps: Option<u16>,
+    pub allowed: bool,
+    pub min_swap_amount: Option<u64>,
+    pub max_swap_amount: Option<u64>,
+    pub min_timelock: Option<u64>,
+    pub max_timelock: Option<u64>,
+    pub reject_freeze_authority: bool,
+    pub reject_permanent_delegate: bool,
+}
+
+impl MintPolicy {
+    /// Validates and applies the admin-controlled fields of this policy
+    fn set_params(&mut self, params: MintPolicyParams) -> Result<()> {
+        let MintPolicyParams {
+            fee_bps,
+            allowed,
+            min_swap_amount,
+            max_swap_amount,
+            min_timelock,
+            max_timelock,
+            reject_freeze_authority,
+            reject_permanent_delegate,
+        } = params;
+        if let Some(fee_bps) = fee_bps {
+            require!(fee_bps <= MAX_FEE_BPS, SwapError::InvalidFeeBps);
+        }
+        if let (Some(min_timelock), Some(max_timelock)) = (min_timelock, max_timelock) {
+            require!(
+                min_timelock <= max_timelock,
+                SwapError::InvalidTimelockBounds
+            );
+        }
+
+        self.fee_bps = fee_bps;
+        self.allowed = allowed;
+        self.min_swap_amount = min_swap_amount;
+        self.max_swap_amount = max_swap_amount;
+        self.min_timelock = min_timelock;
+        self.max_timelock = max_timelock;
+        self.reject_freeze_authority = reject_freeze_authority;
+        self.reject_permanent_delegate = reject_permanent_delegate;
+        Ok(())
+    }
+
+    /// Loads the policy held by a mint policy PDA, if it has been created.
+    /// As the PDA's address is enforced by its seeds, a policy can not be evaded by omitting it.
+    fn load(mint_policy: &AccountInfo) -> Result<Option<MintPolicy>> {
+        if mint_policy.owner != &crate::ID {
+            return Ok(None);
+        }
+        let data = mint_policy.try_borrow_data()?;
+        Ok(Some(MintPolicy::try_deserialize(&mut &data[..])?))
+    }
+}
+
+/// Tracks the tokens of the shared token vault of a mint that are owed to its open swaps,
+/// such that the solvency of the vault can be verified on-chain.
+/// Isolated vaults are not accounted for, as each holds the tokens of a single swap.
+#[account]
+#[derive(InitSpace)]
+pub struct VaultState {
+    /// The bump that derived this PDA
+    pub bump: u8,
+    /// The mint of the token vault
+    pub mint: Pubkey,
+    /// The sum of the `escrowed_amount` of every open swap escrowed in the token vault
+    pub total_locked: u64,
+    /// The number of open swaps escrowed in the token vault
+    pub open_swaps: u64,
+}
+
+impl VaultState {
+    /// Accounts for a swap escrowing `amount` in the token vault upon its initiation
+    fn lock(&mut self, amount: u64) -> Result<()> {
+        self.total_locked = self
+            .total_locked
+            .checked_add(amount)
+            .ok_or(SwapError::VaultAccountingOverflow)?;
+        self.open_swaps = self
+            .open_swaps
+            .checked_add(1)
+            .ok_or(SwapError::VaultAccountingOverflow)?;
+        Ok(())
+    }
+
+    /// Accounts for a swap escrowing `amount` in the token vault upon its completion
+    fn release(&mut self, amount: u64) -> Result<()> {
+        self.total_locked = self
+            .total_locked
+            .checked_sub(amount)
+            .ok_or(SwapError::VaultAccountingUnderflow)?;
+        self.open_swaps = self
+            .open_swaps
+            .checked_sub(1)
+            .ok_or(SwapError::VaultAccountingUnderflow)?;
+        Ok(())
+    }
+}
+
+/// Tracks the nonce of the next `SwapOrder` or `RefundIntent` that a key can sign,
+/// such that every signed order or intent can be executed at most once
+#[account]
+#[derive(InitSpace)]
+pub struct SignerNonce {
+    /// The bump that derived this PDA
+    pub bump: u8,
+    /// The nonce that the next signed order or intent of the key must carry
+    pub next_nonce: u64,
+}
+
+/// A swap order signed off-chain by the owner of the funds, authorizing `initiate_with_signature()`.
+/// The fields are identical to the parameters of `initiate()`, except for the following.  
+/// `mint` is the mint of the tokens to be swapped.  
+/// `nonce` must equal the owner's next nonce, preventing the replay of this order.  
+/// `deadline` is the unix timestamp after which this order can no longer be executed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SwapOrder {
+    pub mint: Pubkey,
+    pub redeemer: Pubkey,
+    pub refundee: Pubkey,
+    pub secret_hash: [u8; 32],
+    pub swap_amount: u64,
+    pub timelock: u64,
+    pub salt: u64,
+    pub timelock_kind: TimelockKind,
+    pub hash_algorithm: HashAlgorithm,
+    pub max_secret_len: u16,
+    pub relayer_tip: u64,
+    pub nonce: u64,
+    pub deadline: i64,
+}
+
+/// An instant refund intent signed off-chain by the redeemer, authorizing `instant_refund_with_signature()`.
+/// `nonce` must equal the redeemer's next nonce, which is consumed by the refund. This prevents the replay
+/// of the intent against a re-initiation of the swap with identical parameters, i.e the same `swap_data` PDA.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RefundIntent {
+    pub swap_data: Pubkey,
+    pub nonce: u64,
+}
+
+/// The clock by which the timelock of an atomic swap is measured.
+/// Wall-clock timelocks ease coordination with counterparty chains whose timelocks
+/// are themselves based on time, as slot times drift.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum TimelockKind {
+    /// The timelock is a number of slots, relative to the slot of initiation
+    Slots,
+    /// The timelock is a number of seconds, relative to the unix timestamp of initiation
+    Seconds,
+    /// The timelock is an absolute unix timestamp
+    Timestamp,
+}
+
+impl TimelockKind {
+    /// The current slot or unix timestamp, as per this kind
+    fn now(self, clock: &Clock) -> Result<u64> {
+        match self {
+            TimelockKind::Slots => Ok(clock.slot),
+            TimelockKind::Seconds | TimelockKind::Timestamp => {
+                u64::try_from(clock.unix_timestamp).map_err(|_| error!(SwapError::TimelockOverflow))
+            }
+        }
+    }
+
+    /// The duration of the timelock in seconds, approximating slots by `DEFAULT_MS_PER_SLOT`
+    /// and rounding up, such that only a zero timelock or a timestamp not in the future
+    /// results in a duration of zero.
+    fn duration_secs(self, timelock: u64, clock: &Clock) -> Result<u64> {
+        Ok(match self {
+            TimelockKind::Slots => timelock.saturating_mul(DEFAULT_MS_PER_SLOT).div_ceil(1000),
+            TimelockKind::Seconds => timelock,
+            TimelockKind::Timestamp => timelock.saturating_sub(self.now(clock)?),
+        })
+    }
+
+    /// Computes the absolute slot or unix timestamp after which (non-instant) refunds are allowed
+    fn expiry(self, timelock: u64, clock: &Clock) -> Result<u64> {
+        match self {
+            TimelockKind::Slots | TimelockKind::Seconds => self
+                .now(clock)?
+                .checked_add(timelock)
+                .ok_or(error!(SwapError::TimelockOverflow)),
+            TimelockKind::Timestamp => Ok(timelock),
+        }
+    }
+}
+
+/// The hash function with which the secret hash of an atomic swap is derived from the secret.
+/// Allows a single secret to be used across every leg of a multi-chain atomic swap.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum HashAlgorithm {
+    /// SHA-256, as used by most HTLCs
+    Sha256,
+    /// Keccak-256, as used by EVM HTLCs
+    Keccak256,
+    /// RIPEMD-160 of SHA-256, as used by legacy Bitcoin scripts.
+    /// The 20 byte digest occupies the beginning of the secret hash, the remaining bytes being zero.
+    Hash160,
+}
+
+impl HashAlgorithm {
+    /// The size of the digest in bytes, before any zero-padding to 32 bytes
+    pub fn digest_len(self) -> usize {
+        match self {
+            HashAlgorithm::Sha256 | HashAlgorithm::Keccak256 => 32,
+            HashAlgorithm::Hash160 => 20,
+        }
+    }
+
+    /// Computes the digest of the given secret, zero-padded to 32 bytes if need be
+    pub fn digest(self, secret: &[u8]) -> [u8; 32] {
+        match self {
+            HashAlgorithm::Sha256 => hash::hash(secret).to_bytes(),
+            HashAlgorithm::Keccak256 => keccak::hash(secret).to_bytes(),
+            HashAlgorithm::Hash160 => {
+                let sha256 = hash::hash(secret).to_bytes();
+                let mut digest = [0; 32];
+                digest[..20].copy_from_slice(&Ripemd160::digest(sha256));
+                digest
+            }
+        }
+    }
+
+    /// Ensures that the unused bytes of a secret hash shorter than 32 bytes are zero,
+    /// so that the secret hash of the swap is unambiguous, and that the maximum secret length
+    /// is within `MAX_SECRET_LEN`
+    fn validate_hashlock(self, secret_hash: &[u8; 32], max_secret_len: u16) -> Result<()> {
+        require!(
+            secret_hash[self.digest_len()..]
+                .iter()
+                .all(|&byte| byte == 0),
+            SwapError::InvalidSecretHash
+        );
+        require!(
+            (1..=MAX_SECRET_LEN).contains(&max_secret_len),
+            SwapError::InvalidMaxSecretLength
+        );
+        Ok(())
+    }
+}
+
+/// Computes the canonical identifier of an atomic swap, allowing the Solana leg of a multi-chain swap
+/// to be correlated with its counterparts, as well as its lifecycle events to be joined on a single key.  
+/// It is the SHA-256 hash of the concatenation of `CHAIN_ID`, the program id, `mint`, `redeemer`,
+/// `refundee`, `secret_hash` and the little-endian bytes of `swap_amount`, `timelock` and `salt`.
+pub fn compute_order_id(
+    mint: &Pubkey,
+    redeemer: &Pubkey,
+    refundee: &Pubkey,
+    secret_hash: &[u8; 32],
+    swap_amount: u64,
+    timelock: u64,
+    salt: u64,
+) -> [u8; 32] {
+    hash::hashv(&[
+        CHAIN_ID,
+        crate::ID.as_ref(),
+        mint.as_ref(),
+        redeemer.as_ref(),
+        refundee.as_ref(),
+        secret_hash,
+        &swap_amount.to_le_bytes(),
+        &timelock.to_le_bytes(),
+        &salt.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Ensures that the instruction immediately preceding the current one is an Ed25519 program instruction
+/// verifying a single signature by `signer` over `message`.  
+/// The Ed25519 program fails the transaction upon an invalid signature,
+/// hence its presence with matching contents suffices.
+fn verify_ed25519_signature(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    // Layout of the Ed25519 program's instruction data, as per the `ed25519_program` docs
+    const OFFSETS_START: usize = 2;
+    const OFFSETS_LEN: usize = 14;
+    const THIS_INSTRUCTION: u16 = u16::MAX;
+
+    let current_index = instructions_sysvar::load_current_index_checked(instructions)?;
+    let ed25519_index = current_index
+        .checked_sub(1)
+        .ok_or(SwapError::InvalidSignature)?;
+    let ed25519_ix =
+        instructions_sysvar::load_instruction_at_checked(ed25519_index.into(), instructions)?;
+    require_keys_eq!(
+        ed25519_ix.program_id,
+        ed25519_program::ID,
+        SwapError::InvalidSignature
+    );
+
+    let data = &ed25519_ix.data;
+    require!(
+        data.len() >= OFFSETS_START + OFFSETS_LEN && data[0] == 1,
+        SwapError::InvalidSignature
+    );
+    // Reads the `index`-th field of the `Ed25519SignatureOffsets` struct
+    let offset = |index: usize| {
+        let start = OFFSETS_START + 2 * index;
+        usize::from(u16::from_le_bytes([data[start], data[start + 1]]))
+    };
+    // The signature, public key and message must all reside within the Ed25519 instruction itself
+    require!(
+        [offset(1), offset(3), offset(6)]
+            .iter()
+            .all(|&index| index == usize::from(THIS_INSTRUCTION)),
+        SwapError::InvalidSignature
+    );
+
+    let (public_key_offset, message_offset, message_len) = (offset(2), offset(4), offset(5));
+    let signed_public_key = data.get(public_key_offset..public_key_offset + 32);
+    let signed_message = data.get(message_offset..message_offset + message_len);
+    require!(
+        signed_public_key == Some(signer.as_ref()) && signed_message == Some(message),
+        SwapError::InvalidSignature
+    );
+
+    Ok(())
+}
+
+/// Whether `mint` has a permanent delegate, as per the Token-2022 PermanentDelegate extension
+fn has_permanent_delegate(mint: &InterfaceAccount<Mint>) -> Result<bool> {
+    let mint_info = mint.to_account_info();
+    let data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(mint
+        .get_extension::<PermanentDelegate>()
+        .is_ok_and(|extension| Option::<Pubkey>::from(extension.delegate).is_some()))
+}
+
+/// The quantity of tokens deposited into `token_vault` since its balance was `balance_before`,
+/// which is less than the quantity transferred if the mint withholds a transfer fee
+fn deposited_amount(
+    token_vault: &mut InterfaceAccount<TokenAccount>,
+    balance_before: u64,
+) -> Result<u64> {
+    token_vault.reload()?;
+    token_vault
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(error!(SwapError::VaultBalanceDecreased))
+}
+
+/// The accounts through which a swap of tokens is completed, common to every instruction completing it
+struct SwapVault<'a, 'info> {
+    swap_data: &'a SwapAccount,
+    identity_pda: &'a AccountInfo<'info>,
+    mint: &'a InterfaceAccount<'info, Mint>,
+    token_vault: &'a InterfaceAccount<'info, TokenAccount>,
+    vault_state: &'a mut Account<'info, VaultState>,
+    rent_sponsor: &'a AccountInfo<'info>,
+    token_program: &'a Interface<'info, TokenInterface>,
+}
+
+impl<'info> SwapVault<'_, 'info> {
+    /// Transfers `amount` escrowed tokens to `to`
+    fn transfer(&self, to: &InterfaceAccount<'info, TokenAccount>, amount: u64) -> Result<()> {
+        transfer_from_vault(
+            self.token_program,
+            self.token_vault,
+            self.mint,
+            &to.to_account_info(),
+            self.identity_pda,
+            self.swap_data.identity_pda_bump,
+            amount,
+        )
+    }
+
+    /// Pays the protocol fee of the swap, if any, to `fee_token_account`, returning the amount paid.
+    /// As with swaps of native SOL, the fee is waived if the token account of the fee recipient
+    /// can not be credited, i.e it has been closed or frozen, rather than preventing the redeem.
+    fn pay_protocol_fee(&self, fee_token_account: Option<&UncheckedAccount<'info>>) -> Result<u64> {
+        let fee_amount = self.swap_data.protocol_fee()?;
+        if fee_amount == 0 {
+            return Ok(0);
+        }
+        let fee_token_account = fee_token_account.ok_or(SwapError::FeeAccountRequired)?;
+        if !can_receive_tokens(fee_token_account, self.token_program.key, &self.mint.key()) {
+            return Ok(0);
+        }
+        transfer_from_vault(
+            self.token_program,
+            self.token_vault,
+            self.mint,
+            fee_token_account,
+            self.identity_pda,
+            self.swap_data.identity_pda_bump,
+            fee_amount,
+        )?;
+        Ok(fee_amount)
+    }
+
+    /// Pays the relayer tip of the swap, if any, to `relayer_token_account`
+    fn pay_relayer_tip(
+        &self,
+        relayer_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+    ) -> Result<()> {
+        let relayer_tip = self.swap_data.relayer_tip;
+        if relayer_tip > 0 {
+            let relayer_token_account =
+                relayer_token_account.ok_or(SwapError::RelayerAccountRequired)?;
+            self.transfer(relayer_token_account, relayer_tip)?;
+        }
+        Ok(())
+    }
+
+    /// Closes the token vault if it is isolated to the swap, transferring any tokens sent to it beyond
+    /// the escrowed amount to `destination`, or otherwise releases the escrowed amount
+    /// from the accounting of the shared token vault. To be called once the escrowed amount is paid out.
+    fn release(self, destination: &InterfaceAccount<'info, TokenAccount>) -> Result<()> {
+        if self.swap_data.vault_isolated {
+            close_isolated_vault(
+                self.token_program,
+                self.token_vault,
+                self.mint,
+                destination,
+                self.rent_sponsor,
+                self.identity_pda,
+                self.swap_data.identity_pda_bump,
+            )
+        } else {
+            self.vault_state.release(self.swap_data.escrowed_amount)
+        }
+    }
+}
+
+/// Redeems the swap escrowed in `vault` with `secret`, paying out the escrowed amount to `destination`,
+/// net of the protocol fee and of the relayer tip. Returns the event to be emitted by the caller.
+fn complete_redeem<'info>(
+    vault: SwapVault<'_, 'info>,
+    secret: Vec<u8>,
+    destination: &InterfaceAccount<'info, TokenAccount>,
+    fee_token_account: Option<&UncheckedAccount<'info>>,
+    relayer_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+) -> Result<Redeemed> {
+    let swap_data = vault.swap_data;
+    swap_data.verify_secret(&secret)?;
+
+    let fee_amount = vault.pay_protocol_fee(fee_token_account)?;
+    vault.pay_relayer_tip(relayer_token_account)?;
+    vault.transfer(
+        destination,
+        swap_data.escrowed_amount - fee_amount - swap_data.relayer_tip,
+    )?;
+    vault.release(destination)?;
+
+    Ok(Redeemed {
+        mint: swap_data.mint,
+        redeemer: swap_data.redeemer,
+        refundee: swap_data.refundee,
+        secret,
+        swap_amount: swap_data.swap_amount,
+        fee_amount,
+        relayer_tip: swap_data.relayer_tip,
+        timelock: swap_data.timelock,
+        salt: swap_data.salt,
+        order_id: swap_data.order_id,
+        current_redeemer: swap_data.current_redeemer,
+        destination: destination.key(),
+    })
+}
+
+/// Refunds the swap escrowed in `vault` once expired, paying out the escrowed amount to `destination`,
+/// net of the relayer tip. Returns the event to be emitted by the caller.
+fn complete_refund<'info>(
+    vault: SwapVault<'_, 'info>,
+    destination: &InterfaceAccount<'info, TokenAccount>,
+    relayer_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+) -> Result<Refunded> {
+    let swap_data = vault.swap_data;
+    require!(
+        swap_data.has_expired(&Clock::get()?)?,
+        SwapError::RefundBeforeExpiry
+    );
+
+    vault.pay_relayer_tip(relayer_token_account)?;
+    vault.transfer(
+        destination,
+        swap_data.escrowed_amount - swap_data.relayer_tip,
+    )?;
+    vault.release(destination)?;
+
+    Ok(Refunded {
+        mint: swap_data.mint,
+        redeemer: swap_data.redeemer,
+        refundee: swap_data.refundee,
+        secret_hash: swap_data.secret_hash,
+        swap_amount: swap_data.swap_amount,
+        relayer_tip: swap_data.relayer_tip,
+        timelock: swap_data.timelock,
+        salt: swap_data.salt,
+        order_id: swap_data.order_id,
+        expiry: swap_data.expiry,
+        current_refundee: swap_data.current_refundee,
+        destination: destination.key(),
+    })
+}
+
+/// Refunds the swap escrowed in `vault` with the consent of the redeemer, paying out the whole
+/// escrowed amount to `destination`. Returns the event to be emitted by the caller.
+fn complete_instant_refund<'info>(
+    vault: SwapVault<'_, 'info>,
+    destination: &InterfaceAccount<'info, TokenAccount>,
+) -> Result<InstantRefunded> {
+    let swap_data = vault.swap_data;
+    vault.transfer(destination, swap_data.escrowed_amount)?;
+    vault.release(destination)?;
+
+    Ok(InstantRefunded {
+        mint: swap_data.mint,
+        redeemer: swap_data.redeemer,
+        refundee: swap_data.refundee,
+        secret_hash: swap_data.secret_hash,
+        swap_amount: swap_data.swap_amount,
+        timelock: swap_data.timelock,
+        salt: swap_data.salt,
+        order_id: swap_data.order_id,
+        current_refundee: swap_data.current_refundee,
+        destination: destination.key(),
+    })
+}
+
+/// Whether `account` can be credited `amount` lamports, i.e it is not executable
+/// and holds at least the rent-exempt minimum for its data once credited
+fn can_receive_lamports(account: &AccountInfo, amount: u64, rent: &Rent) -> bool {
+    !account.executable
+        && account
+            .lamports()
+            .checked_add(amount)
+            .is_some_and(|balance| rent.is_exempt(balance, account.data_len()))
+}
+
+/// Whether `account` is a token account of `mint` under `token_program` that can be credited,
+/// i.e it is initialized and not frozen
+fn can_receive_tokens(account: &AccountInfo, token_program: &Pubkey, mint: &Pubkey) -> bool {
+    if account.owner != token_program {
+        return false;
+    }
+    let Ok(data) = account.try_borrow_data() else {
+        return false;
+    };
+    StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data).is_ok_and(|account| {
+        account.base.mint == *mint && account.base.state == AccountState::Initialized
+    })
+}
+
+/// Transfers `amount` tokens of `mint` from the token vault to `to`, as authorized by the `identity_pda`
+fn transfer_from_vault<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    token_vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &AccountInfo<'info>,
+    identity_pda: &AccountInfo<'info>,
+    identity_pda_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let pda_seeds: &[&[&[u8]]] = &[&[&[identity_pda_bump]]];
+    let token_transfer_context = CpiContext::new(
+        token_program.to_account_info(),
+        token_interface::TransferChecked {
+            from: token_vault.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority: identity_pda.to_account_info(),
+        },
+    )
+    .with_signer(pda_seeds);
+    token_interface::transfer_checked(token_transfer_context, amount, mint.decimals)
+}
+
+/// Closes an isolated token vault once the escrowed amount is paid out, refunding its rent to `rent_sponsor`.
+/// As the vault is at a known address, anyone can send tokens to it, which would prevent it from being
+/// closed, hence the remaining balance is transferred to `destination` beforehand.
+/// Likewise, transfer fees withheld in the vault (Token-2022 TransferFee extension) are harvested to the mint.
+fn close_isolated_vault<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    token_vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    destination: &InterfaceAccount<'info, TokenAccount>,
+    rent_sponsor: &AccountInfo<'info>,
+    identity_pda: &AccountInfo<'info>,
+    identity_pda_bump: u8,
+) -> Result<()> {
+    // Read from the account data rather than `token_vault`, which predates the payouts
+    let remaining_amount = {
+        let token_vault_info = token_vault.to_account_info();
+        let data = token_vault_info.try_borrow_data()?;
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?
+            .base
+            .amount
+    };
+    if remaining_amount > 0 {
+        transfer_from_vault(
+            token_program,
+            token_vault,
+            mint,
+            &destination.to_account_info(),
+            identity_pda,
+            identity_pda_bump,
+            remaining_amount,
+        )?;
+    }
+
+    let withheld_amount = {
+        let token_vault_info = token_vault.to_account_info();
+        let data = token_vault_info.try_borrow_data()?;
+        let token_vault = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+        token_vault
+            .get_extension::<TransferFeeAmount>()
+            .map_or(0, |extension| u64::from(extension.withheld_amount))
+    };
+    if withheld_amount > 0 {
+        let harvest_context = CpiContext::new(
+            token_program.to_account_info(),
+            token_interface::HarvestWithheldTokensToMint {
+                token_program_id: token_program.to_account_info(),
+                mint: mint.to_account_info(),
+            },
+        );
+        token_interface::harvest_withheld_tokens_to_mint(
+            harvest_context,
+            vec![token_vault.to_account_info()],
+        )?;
+    }
+
+    let pda_seeds: &[&[&[u8]]] = &[&[&[identity_pda_bump]]];
+    let close_context = CpiContext::new(
+        token_program.to_account_info(),
+        token_interface::CloseAccount {
+            account: token_vault.to_account_info(),
+            destination: rent_sponsor.to_account_info(),
+            authority: identity_pda.to_account_info(),
+        },
+    )
+    .with_signer(pda_seeds);
+    token_interface::close_account(close_context)
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+// The parameters must have the exact name and order as specified in the underlying function
+// to avoid "seed constraint violation" errors.
+// Refer: https://www.anchor-lang.com/docs/references/account-constraints#instruction-attribute
+#[instruction(redeemer: Pubkey, refundee: Pubkey, secret_hash: [u8; 32], swap_amount: u64, timelock: u64, salt: u64)]
+pub struct Initiate<'info> {
+    /// CHECK: Program-derived address used solely as signing authority (no data allocation)
+    #[account(seeds = [], bump)]
+    pub identity_pda: AccountInfo<'info>,
+
+    /// A PDA that maintains the on-chain state of the atomic swap throughout its lifecycle.
+    /// The choice of seeds is to make the already expensive possibility of frontrunning, more expensive.
+    /// The `salt` lets swaps with otherwise identical parameters coexist, and makes frontrunning
+    /// an initiation with identical parameters infeasible without prior knowledge of the salt.
+    /// This PDA will be deleted upon completion of the swap.
+    #[account(
+        init,
+        payer = rent_sponsor,
+        seeds = [
+            mint.key().as_ref(),
+            redeemer.as_ref(),
+            refundee.as_ref(),
+            &secret_hash,
+            &swap_amount.to_le_bytes(),
+            &timelock.to_le_bytes(),
+            &salt.to_le_bytes(),
+        ],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + SwapAccount::INIT_SPACE,
+    )]
+    pub swap_data: Account<'info, SwapAccount>,
+
+    /// A permanent PDA that is controlled by the program through the `identity_pda`, as implied
+    /// by the value of the `authority` field below. As such, it serves as the "vault" by escrowing tokens
+    /// of type `mint` for the atomic swap.  
+    /// It is intended to be reused for all swaps involving the same mint.  
+    /// Just like `identity_pda`, it will be created during the first most invocation of `initiate()`
+    /// of every distinct mint using the `init_if_needed` attribute, under whichever token program
+    /// (Token or Token-2022) owns the mint.
+    #[account(
+        init_if_needed,
+        payer = rent_sponsor,
+        seeds = [mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = identity_pda,
+        token::token_program = token_program,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The accounting of the shared token vault of `mint`, created alongside it
+    #[account(
+        init_if_needed,
+        payer = rent_sponsor,
+        seeds = [b"vault_state", mint.key().as_ref()],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + VaultState::INIT_SPACE,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: The program configuration PDA, determining the protocol fee of this swap.
+    /// It may not have been created, in which case initiations are unrestricted and free of fees.
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    /// CHECK: The policy PDA of `mint`, which may not have been created.
+    /// Its contents are validated upon being loaded.
+    #[account(seeds = [b"mint_policy", mint.key().as_ref()], bump)]
+    pub mint_policy: UncheckedAccount<'info>,
+
+    /// The party that deposits the funds to be involved in the atomic swap.
+    /// They must sign this transaction.
+    pub funder: Signer<'info>,
+
+    /// The token account of the funder
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = funder,
+        token::token_program = token_program,
+    )]
+    pub funder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The mint of the tokens involved in this swap. As this is a parameter, this program can thus be reused
+    /// for atomic swaps with different mints, owned by either the Token or the Token-2022 program.
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Any entity that pays the PDA rent.
+    /// Upon completion of the swap, the PDA rent refund resulting from the
+    /// deletion of `swap_data` will be refunded to this address.
+    #[account(mut)]
+    pub rent_sponsor: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+// The parameters must have the exact name and order as specified in the underlying function
+// to avoid "seed constraint violation" errors.
+#[instruction(redeemer: Pubkey, refundee: Pubkey, secret_hash: [u8; 32], swap_amount: u64, timelock: u64, salt: u64)]
+pub struct InitiateIsolated<'info> {
+    /// CHECK: Program-derived address used solely as signing authority (no data allocation)
+    #[account(seeds = [], bump)]
+    pub identity_pda: AccountInfo<'info>,
+
+    /// A PDA that maintains the on-chain state of the atomic swap throughout its lifecycle.
+    /// The seeds are identical to that of `initiate()`, such that a swap is either isolated or not.
+    /// This PDA will be deleted upon completion of the swap.
+    #[account(
+        init,
+        payer = rent_sponsor,
+        seeds = [
+            mint.key().as_ref(),
+            redeemer.as_ref(),
+            refundee.as_ref(),
+            &secret_hash,
+            &swap_amount.to_le_bytes(),
+            &timelock.to_le_bytes(),
+            &salt.to_le_bytes(),
+        ],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + SwapAccount::INIT_SPACE,
+    )]
+    pub swap_data: Account<'info, SwapAccount>,
+
+    /// A token account controlled by the program through the `identity_pda`, escrowing the tokens
+    /// of this atomic swap only. It is created under the same token program as the mint
+    /// and will be closed upon completion of the swap.
+    #[account(
+        init,
+        payer = rent_sponsor,
+        seeds = [swap_data.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = identity_pda,
+        token::token_program = token_program,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The accounting of the shared token vault of `mint`, which this swap is not accounted in.
+    /// It is nonetheless created if needed, as the completion of any swap of `mint` requires it.
+    #[account(
+        init_if_needed,
+        payer = rent_sponsor,
+        seeds = [b"vault_state", mint.key().as_ref()],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + VaultState::INIT_SPACE,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: The program configuration PDA, determining the protocol fee of this swap.
+    /// It may not have been created, in which case initiations are unrestricted and free of fees.
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    /// CHECK: The policy PDA of `mint`, which may not have been created.
+    /// Its contents are validated upon being loaded.
+    #[account(seeds = [b"mint_policy", mint.key().as_ref()], bump)]
+    pub mint_policy: UncheckedAccount<'info>,
+
+    /// The party that deposits the funds to be involved in the atomic swap.
+    /// They must sign this transaction.
+    pub funder: Signer<'info>,
+
+    /// The token account of the funder
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = funder,
+        token::token_program = token_program,
+    )]
+    pub funder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The mint of the tokens involved in this swap. As this is a parameter, this program can thus be reused
+    /// for atomic swaps with different mints, owned by either the Token or the Token-2022 program.
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Any entity that pays the PDA rent.
+    /// Upon completion of the swap, the PDA rent refund resulting from the
+    /// deletion of `swap_data` will be refunded to this address.
+    #[account(mut)]
+    pub rent_sponsor: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Redeem<'info> {
+    /// CHECK: The Identity PDA, used only for authorizing token transfers, no data is read or written to it
+    #[account(seeds = [], bump = swap_data.identity_pda_bump)]
+    pub identity_pda: AccountInfo<'info>,
+
+    /// The PDA holding the state information of the atomic swap. Will be closed upon successful execution
+    /// and the resulting rent refund will be sent to the rent_sponsor.
+    #[account(
+        mut,
+        seeds = [
+            swap_data.mint.as_ref(),
+            swap_data.redeemer.as_ref(),
+            swap_data.refundee.as_ref(),
+            &swap_data.secret_hash,
+            &swap_data.swap_amount.to_le_bytes(),
+            &swap_data.timelock.to_le_bytes(),
+            &swap_data.salt.to_le_bytes(),
+        ],
+        bump = swap_data.bump,
+        close = rent_sponsor,
+    )]
+    pub swap_data: Account<'info, SwapAccount>,
+
+    /// The mint of the tokens involved in this swap, needed for checked transfers.
+    /// It is writable, as transfer fees withheld in an isolated vault are harvested to it.
+    #[account(mut, address = swap_data.mint, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// A token account controlled by the program, escrowing the tokens for this atomic swap.
+    /// It is closed upon successful execution if it is isolated to this swap.
+    #[account(
+        mut,
+        address = swap_data.token_vault @ SwapError::InvalidTokenVault,
+        token::mint = mint,
+        token::authority = identity_pda,
+        token::token_program = token_program,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The accounting of the shared token vault of the mint
+    #[account(
+        mut,
+        seeds = [b"vault_state", swap_data.mint.as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: The token account of the redeemer
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = swap_data.current_redeemer,
+        token::token_program = token_program,
+    )]
+    pub redeemer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: The associated token account of the fee recipient, only required if the protocol fee
+    /// of this swap is non-zero. It is unchecked as it may have been closed or frozen since the initiation,
+    /// in which case the fee is waived, and is otherwise validated before being credited.
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &swap_data.fee_recipient,
+            &mint.key(),
+            &token_program.key(),
+        ) @ SwapError::InvalidFeeRecipient,
+    )]
+    pub fee_token_account: Option<UncheckedAccount<'info>>,
+
+    /// The submitter of this transaction, typically its fee payer, to whom the relayer tip is paid
+    pub relayer: Signer<'info>,
+
+    /// The token account of the relayer. Only required if the relayer tip of this swap is non-zero.
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = relayer,
+        token::token_program = token_program,
+    )]
+    pub relayer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Rent sponsor's address for refunding PDA rent
+    #[account(mut, address = swap_data.rent_sponsor @ SwapError::InvalidRentSponsor)]
+    pub rent_sponsor: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemWithAta<'info> {
+    /// CHECK: The Identity PDA, used only for authorizing token transfers, no data is read or written to it
+    #[account(seeds = [], bump = swap_data.identity_pda_bump)]
+    pub identity_pda: AccountInfo<'info>,
+
+    /// The PDA holding the state information of the atomic swap. Will be closed upon successful execution
+    /// and the resulting rent refund will be sent to the rent_sponsor.
+    #[account(
+        mut,
+        seeds = [
+            swap_data.mint.as_ref(),
+            swap_data.redeemer.as_ref(),
+            swap_data.refundee.as_ref(),
+            &swap_data.secret_hash,
+            &swap_data.swap_amount.to_le_bytes(),
+            &swap_data.timelock.to_le_bytes(),
+            &swap_data.salt.to_le_bytes(),
+        ],
+        bump = swap_data.bump,
+        close = rent_sponsor,
+    )]
+    pub swap_data: Account<'info, SwapAccount>,
+
+    /// The mint of the tokens involved in this swap, needed for checked transfers.
+    /// It is writable, as transfer fees withheld in an isolated vault are harvested to it.
+    #[account(mut, address = swap_data.mint, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// A token account controlled by the program, escrowing the tokens for this atomic swap.
+    /// It is closed upon successful execution if it is isolated to this swap.
+    #[account(
+        mut,
+        address = swap_data.token_vault @ SwapError::InvalidTokenVault,
+        token::mint = mint,
+        token::authority = identity_pda,
+        token::token_program = token_program,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The accounting of the shared token vault of the mint
+    #[account(
+        mut,
+        seeds = [b"vault_state", swap_data.mint.as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: The redeemer of the atomic swap, owning `redeemer_token_account`
+    #[account(address = swap_data.current_redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: UncheckedAccount<'info>,
+
+    /// The associated token account of the redeemer, created if it does not exist
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        associated_token::mint = mint,
+        associated_token::authority = redeemer,
+        associated_token::token_program = token_program,
+    )]
+    pub redeemer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: The associated token account of the fee recipient, only required if the protocol fee
+    /// of this swap is non-zero. It is unchecked as it may have been closed or frozen since the initiation,
+    /// in which case the fee is waived, and is otherwise validated before being credited.
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &swap_data.fee_recipient,
+            &mint.key(),
+            &token_program.key(),
+        ) @ SwapError::InvalidFeeRecipient,
+    )]
+    pub fee_token_account: Option<UncheckedAccount<'info>>,
+
+    /// The submitter of this transaction, typically its fee payer, to whom the relayer tip is paid.
+    /// They pay the rent of `redeemer_token_account` if it is created.
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// The token account of the relayer. Only required if the relayer tip of this swap is non-zero.
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = relayer,
+        token::token_program = token_program,
+    )]
+    pub relayer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Rent sponsor's address for refunding PDA rent
+    #[account(mut, address = swap_data.rent_sponsor @ SwapError::InvalidRentSponsor)]
+    pub rent_sponsor: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemTo<'info> {
+    /// CHECK: The Identity PDA, used only for authorizing token transfers, no data is read or written to it
+    #[account(seeds = [], bump = swap_data.identity_pda_bump)]
+    pub identity_pda: AccountInfo<'info>,
+
+    /// The PDA holding the state information of the atomic swap. Will be closed upon successful execution
+    /// and the resulting rent refund will be sent to the rent_sponsor.
+    #[account(
+        mut,
+        seeds = [
+            swap_data.mint.as_ref(),
+            swap_data.redeemer.as_ref(),
+            swap_data.refundee.as_ref(),
+            &swap_data.secret_hash,
+            &swap_data.swap_amount.to_le_bytes(),
+            &swap_data.timelock.to_le_bytes(),
+            &swap_data.salt.to_le_bytes(),
+        ],
+        bump = swap_data.bump,
+        close = rent_sponsor,
+    )]
+    pub swap_data: Account<'info, SwapAccount>,
+
+    /// The mint of the tokens involved in this swap, needed for checked transfers.
+    /// It is writable, as transfer fees withheld in an isolated vault are harvested to it.
+    #[account(mut, address = swap_data.mint, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// A token account controlled by the program, escrowing the tokens for this atomic swap.
+    /// It is closed upon successful execution if it is isolated to this swap.
+    #[account(
+        mut,
+        address = swap_data.token_vault @ SwapError::InvalidTokenVault,
+        token::mint = mint,
+        token::authority = identity_pda,
+        token::token_program = token_program,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The accounting of the shared token vault of the mint
+    #[account(
+        mut,
+        seeds = [b"vault_state", swap_data.mint.as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// The redeemer of the atomic swap. They must sign this transaction.
+    #[account(address = swap_data.current_redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: Signer<'info>,
+
+    /// Any token account of the mint, chosen by the redeemer to receive the funds
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: The associated token account of the fee recipient, only required if the protocol fee
+    /// of this swap is non-zero. It is unchecked as it may have been closed or frozen since the initiation,
+    /// in which case the fee is waived, and is otherwise validated before being credited.
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &swap_data.fee_recipient,
+            &mint.key(),
+            &token_program.key(),
+        ) @ SwapError::InvalidFeeRecipient,
+    )]
+    pub fee_token_account: Option<UncheckedAccount<'info>>,
+
+    /// The submitter of this transaction, typically its fee payer, to whom the relayer tip is paid
+    pub relayer: Signer<'info>,
+
+    /// The token account of the relayer. Only required if the relayer tip of this swap is non-zero.
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = relayer,
+        token::token_program = token_program,
+    )]
+    pub relayer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Rent sponsor's address for refunding PDA rent
+    #[account(mut, address = swap_data.rent_sponsor @ SwapError::InvalidRentSponsor)]
+    pub rent_sponsor: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Refund<'info> {
+    /// CHECK: The Identity PDA, used solely for authorizing token transfers, no data is read or written to it
+    #[account(seeds = [], bump = swap_data.identity_pda_bump)]
+    pub identity_pda: AccountInfo<'info>,
+
+    /// The PDA holding the state information of the atomic swap. Will be closed upon successful execution
+    /// and the resulting rent refund will be sent to the rent_sponsor.
+    #[account(
+        mut,
+        seeds = [
+            swap_data.mint.as_ref(),
+            swap_data.redeemer.as_ref(),
+            swap_data.refundee.as_ref(),
+            &swap_data.secret_hash,
+            &swap_data.swap_amount.to_le_bytes(),
+            &swap_data.timelock.to_le_bytes(),
+            &swap_data.salt.to_le_bytes(),
+        ],
+        bump = swap_data.bump,
+        close = rent_sponsor,
+    )]
+    pub swap_data: Account<'info, SwapAccount>,
+
+    /// The mint of the tokens involved in this swap, needed for checked transfers.
+    /// It is writable, as transfer fees withheld in an isolated vault are harvested to it.
+    #[account(mut, address = swap_data.mint, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// A token account controlled by the program, escrowing the tokens for this atomic swap.
+    /// It is closed upon successful execution if it is isolated to this swap.
+    #[account(
+        mut,
+        address = swap_data.token_vault @ SwapError::InvalidTokenVault,
+        token::mint = mint,
+        token::authority = identity_pda,
+        token::token_program = token_program,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The accounting of the shared token vault of the mint
+    #[account(
+        mut,
+        seeds = [b"vault_state", swap_data.mint.as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: The token account of the refundee
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = swap_data.current_refundee,
+        token::token_program = token_program,
+    )]
+    pub refundee_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The submitter of this transaction, typically its fee payer, to whom the relayer tip is paid
+    pub relayer: Signer<'info>,
+
+    /// The token account of the relayer. Only required if the relayer tip of this swap is non-zero.
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = relayer,
+        token::token_program = token_program,
+    )]
+    pub relayer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Rent sponsor's address for refunding PDA rent
+    #[account(mut, address = swap_data.rent_sponsor @ SwapError::InvalidRentSponsor)]
+    pub rent_sponsor: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundTo<'info> {
+    /// CHECK: The Identity PDA, used solely for authorizing token transfers, no data is read or written to it
+    #[account(seeds = [], bump = swap_data.identity_pda_bump)]
+    pub identity_pda: AccountInfo<'info>,
+
+    /// The PDA holding the state information of the atomic swap. Will be closed upon successful execution
+    /// and the resulting rent refund will be sent to the rent_sponsor.
+    #[account(
+        mut,
+        seeds = [
+            swap_data.mint.as_ref(),
+            swap_data.redeemer.as_ref(),
+            swap_data.refundee.as_ref(),
+            &swap_data.secret_hash,
+            &swap_data.swap_amount.to_le_bytes(),
+            &swap_data.timelock.to_le_bytes(),
+            &swap_data.salt.to_le_bytes(),
+        ],
+        bump = swap_data.bump,
+        close = rent_sponsor,
+    )]
+    pub swap_data: Account<'info, SwapAccount>,
+
+    /// The mint of the tokens involved in this swap, needed for checked transfers.
+    /// It is writable, as transfer fees withheld in an isolated vault are harvested to it.
+    #[account(mut, address = swap_data.mint, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// A token account controlled by the program, escrowing the tokens for this atomic swap.
+    /// It is closed upon successful execution if it is isolated to this swap.
+    #[account(
+        mut,
+        address = swap_data.token_vault @ SwapError::InvalidTokenVault,
+        token::mint = mint,
+        token::authority = identity_pda,
+        token::token_program = token_program,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The accounting of the shared token vault of the mint
+    #[account(
+        mut,
+        seeds = [b"vault_state", swap_data.mint.as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// The current refundee of the atomic swap. They must sign this transaction.
+    #[account(address = swap_data.current_refundee @ SwapError::InvalidRefundee)]
+    pub refundee: Signer<'info>,
+
+    /// Any token account of the mint, chosen by the refundee to receive the funds
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The submitter of this transaction, typically its fee payer, to whom the relayer tip is paid
+    pub relayer: Signer<'info>,
+
+    /// The token account of the relayer. Only required if the relayer tip of this swap is non-zero.
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = relayer,
+        token::token_program = token_program,
+    )]
+    pub relayer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Rent sponsor's address for refunding PDA rent
+    #[account(mut, address = swap_data.rent_sponsor @ SwapError::InvalidRentSponsor)]
+    pub rent_sponsor: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundWithAta<'info> {
+    /// CHECK: The Identity PDA, used solely for authorizing token transfers, no data is read or written to it
+    #[account(seeds = [], bump = swap_data.identity_pda_bump)]
+    pub identity_pda: AccountInfo<'info>,
+
+    /// The PDA holding the state information of the atomic swap. Will be closed upon successful execution
+    /// and the resulting rent refund will be sent to the rent_sponsor.
+    #[account(
+        mut,
+        seeds = [
+            swap_data.mint.as_ref(),
+            swap_data.redeemer.as_ref(),
+            swap_data.refundee.as_ref(),
+            &swap_data.secret_hash,
+            &swap_data.swap_amount.to_le_bytes(),
+            &swap_data.timelock.to_le_bytes(),
+            &swap_data.salt.to_le_bytes(),
+        ],
+        bump = swap_data.bump,
+        close = rent_sponsor,
+    )]
+    pub swap_data: Account<'info, SwapAccount>,
+
+    /// The mint of the tokens involved in this swap, needed for checked transfers.
+    /// It is writable, as transfer fees withheld in an isolated vault are harvested to it.
+    #[account(mut, address = swap_data.mint, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// A token account controlled by the program, escrowing the tokens for this atomic swap.
+    /// It is closed upon successful execution if it is isolated to this swap.
+    #[account(
+        mut,
+        address = swap_data.token_vault @ SwapError::InvalidTokenVault,
+        token::mint = mint,
+        token::authority = identity_pda,
+        token::token_program = token_program,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The accounting of the shared token vault of the mint
+    #[account(
+        mut,
+        seeds = [b"vault_state", swap_data.mint.as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: The refundee of the atomic swap, owning `refundee_token_account`
+    #[account(address = swap_data.current_refundee @ SwapError::InvalidRefundee)]
+    pub refundee: UncheckedAccount<'info>,
+
+    /// The associated token account of the refundee, created if it does not exist
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        associated_token::mint = mint,
+        associated_token::authority = refundee,
+        associated_token::token_program = token_program,
+    )]
+    pub refundee_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The submitter of this transaction, typically its fee payer, to whom the relayer tip is paid.
+    /// They pay the rent of `refundee_token_account` if it is created.
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// The token account of the relayer. Only required if the relayer tip of this swap is non-zero.
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = relayer,
+        token::token_program = token_program,
+    )]
+    pub relayer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Rent sponsor's address for refunding PDA rent
+    #[account(mut, address = swap_data.rent_sponsor @ SwapError::InvalidRentSponsor)]
+    pub rent_sponsor: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InstantRefund<'info> {
+    /// CHECK: The Identity PDA, used solely for authorizing token transfers, no data is read or written to it
+    #[account(seeds = [], bump = swap_data.identity_pda_bump)]
+    pub identity_pda: AccountInfo<'info>,
+
+    /// The PDA holding the state information of the atomic swap. Will be closed upon successful execution
+    /// and the resulting rent refund will be sent to the rent_sponsor.
+    #[account(
+        mut,
+        seeds = [
+            swap_data.mint.as_ref(),
+            swap_data.redeemer.as_ref(),
+            swap_data.refundee.as_ref(),
+            &swap_data.secret_hash,
+            &swap_data.swap_amount.to_le_bytes(),
+            &swap_data.timelock.to_le_bytes(),
+            &swap_data.salt.to_le_bytes(),
+        ],
+        bump = swap_data.bump,
+        close = rent_sponsor,
+    )]
+    pub swap_data: Account<'info, SwapAccount>,
+
+    /// The mint of the tokens involved in this swap, needed for checked transfers.
+    /// It is writable, as transfer fees withheld in an isolated vault are harvested to it.
+    #[account(mut, address = swap_data.mint, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// A token account controlled by the program, escrowing the tokens for this atomic swap.
+    /// It is closed upon successful execution if it is isolated to this swap.
+    #[account(
+        mut,
+        address = swap_data.token_vault @ SwapError::InvalidTokenVault,
+        token::mint = mint,
+        token::authority = identity_pda,
+        token::token_program = token_program,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The accounting of the shared token vault of the mint
+    #[account(
+        mut,
+        seeds = [b"vault_state", swap_data.mint.as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: The token account of the refundee
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = swap_data.current_refundee,
+        token::token_program = token_program,
+    )]
+    pub refundee_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The redeemer of the atomic swap. They must sign this transaction.
+    #[account(mut, address = swap_data.current_redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: Signer<'info>,
+
+    /// CHECK: Rent sponsor's address for PDA rent refund
+    #[account(mut, address = swap_data.rent_sponsor @ SwapError::InvalidRentSponsor)]
+    pub rent_sponsor: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InstantRefundWithAta<'info> {
+    /// CHECK: The Identity PDA, used solely for authorizing token transfers, no data is read or written to it
+    #[account(seeds = [], bump = swap_data.identity_pda_bump)]
+    pub identity_pda: AccountInfo<'info>,
+
+    /// The PDA holding the state information of the atomic swap. Will be closed upon successful execution
+    /// and the resulting rent refund will be sent to the rent_sponsor.
+    #[account(
+        mut,
+        seeds = [
+            swap_data.mint.as_ref(),
+            swap_data.redeemer.as_ref(),
+            swap_data.refundee.as_ref(),
+            &swap_data.secret_hash,
+            &swap_data.swap_amount.to_le_bytes(),
+            &swap_data.timelock.to_le_bytes(),
+            &swap_data.salt.to_le_bytes(),
+        ],
+        bump = swap_data.bump,
+        close = rent_sponsor,
+    )]
+    pub swap_data: Account<'info, SwapAccount>,
+
+    /// The mint of the tokens involved in this swap, needed for checked transfers.
+    /// It is writable, as transfer fees withheld in an isolated vault are harvested to it.
+    #[account(mut, address = swap_data.mint, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// A token account controlled by the program, escrowing the tokens for this atomic swap.
+    /// It is closed upon successful execution if it is isolated to this swap.
+    #[account(
+        mut,
+        address = swap_data.token_vault @ SwapError::InvalidTokenVault,
+        token::mint = mint,
+        token::authority = identity_pda,
+        token::token_program = token_program,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The accounting of the shared token vault of the mint
+    #[account(
+        mut,
+        seeds = [b"vault_state", swap_data.mint.as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: The refundee of the atomic swap, owning `refundee_token_account`
+    #[account(address = swap_data.current_refundee @ SwapError::InvalidRefundee)]
+    pub refundee: UncheckedAccount<'info>,
+
+    /// The associated token account of the refundee, created if it does not exist
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        associated_token::mint = mint,
+        associated_token::authority = refundee,
+        associated_token::token_program = token_program,
+    )]
+    pub refundee_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The redeemer of the atomic swap. They must sign this transaction.
+    #[account(mut, address = swap_data.current_redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: Signer<'info>,
+
+    /// The submitter of this transaction, typically its fee payer.
+    /// They pay the rent of `refundee_token_account` if it is created.
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// CHECK: Rent sponsor's address for PDA rent refund
+    #[account(mut, address = swap_data.rent_sponsor @ SwapError::InvalidRentSponsor)]
+    pub rent_sponsor: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+// The parameters must have the exact name and order as specified in the underlying function
+// to avoid "seed constraint violation" errors.
+#[instruction(redeemer: Pubkey, refundee: Pubkey, secret_hash: [u8; 32], swap_amount: u64, timelock: u64, salt: u64)]
+pub struct InitiateNative<'info> {
+    /// A PDA that maintains the on-chain state of the atomic swap throughout its lifecycle.
+    /// Alongside its rent, it escrows the lamports of the atomic swap.
+    /// The seeds are identical to that of `initiate()`, with `NATIVE_MINT` in place of the mint.
+    /// This PDA will be deleted upon completion of the swap.
+    #[account(
+        init,
+        payer = rent_sponsor,
+        seeds = [
+            NATIVE_MINT.as_ref(),
+            redeemer.as_ref(),
+            refundee.as_ref(),
+            &secret_hash,
+            &swap_amount.to_le_bytes(),
+            &timelock.to_le_bytes(),
+            &salt.to_le_bytes(),
+        ],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + SwapAccount::INIT_SPACE,
+    )]
+    pub swap_data: Account<'info, SwapAccount>,
+
+    /// CHECK: The program configuration PDA, determining the protocol fee of this swap.
+    /// It may not have been created, in which case initiations are unrestricted and free of fees.
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    /// CHECK: The policy PDA of `NATIVE_MINT`, which may not have been created.
+    /// Its contents are validated upon being loaded.
+    #[account(seeds = [b"mint_policy", NATIVE_MINT.as_ref()], bump)]
+    pub mint_policy: UncheckedAccount<'info>,
+
+    /// The party that deposits the lamports to be involved in the atomic swap.
+    /// They must sign this transaction.
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    /// Any entity that pays the PDA rent.
+    /// Upon completion of the swap, the PDA rent refund resulting from the
+    /// deletion of `swap_data` will be refunded to this address.
+    #[account(mut)]
+    pub rent_sponsor: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemNative<'info> {
+    /// The PDA holding the state information and the lamports of the atomic swap.
+    /// Will be closed upon successful execution and the resulting rent refund will be sent to the rent_sponsor.
+    #[account(
+        mut,
+        seeds = [
+            swap_data.mint.as_ref(),
+            swap_data.redeemer.as_ref(),
+            swap_data.refundee.as_ref(),
+            &swap_data.secret_hash,
+            &swap_data.swap_amount.to_le_bytes(),
+            &swap_data.timelock.to_le_bytes(),
+            &swap_data.salt.to_le_bytes(),
+        ],
+        bump = swap_data.bump,
+        constraint = swap_data.mint == NATIVE_MINT @ SwapError::NotNativeSwap,
+        close = rent_sponsor,
+    )]
+    pub swap_data: Account<'info, SwapAccount>,
+
+    /// The redeemer of the atomic swap, receiving the escrowed lamports
+    #[account(mut, address = swap_data.current_redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: SystemAccount<'info>,
+
+    /// CHECK: The recipient of the protocol fee, which is only credited lamports.
+    /// Only required if the protocol fee of this swap is non-zero.
+    #[account(mut, address = swap_data.fee_recipient @ SwapError::InvalidFeeRecipient)]
+    pub fee_recipient: Option<UncheckedAccount<'info>>,
+
+    /// The submitter of this transaction, typically its fee payer, to whom the relayer tip is paid
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// CHECK: Rent sponsor's address for refunding PDA rent
+    #[account(mut, address = swap_data.rent_sponsor @ SwapError::InvalidRentSponsor)]
+    pub rent_sponsor: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundNative<'info> {
+    /// The PDA holding the state information and the lamports of the atomic swap.
+    /// Will be closed upon successful execution and the resulting rent refund will be sent to the rent_sponsor.
+    #[account(
+        mut,
+        seeds = [
+            swap_data.mint.as_ref(),
+            swap_data.redeemer.as_ref(),
+            swap_data.refundee.as_ref(),
+            &swap_data.secret_hash,
+            &swap_data.swap_amount.to_le_bytes(),
+            &swap_data.timelock.to_le_bytes(),
+            &swap_data.salt.to_le_bytes(),
+        ],
+        bump = swap_data.bump,
+        constraint = swap_data.mint == NATIVE_MINT @ SwapError::NotNativeSwap,
+        close = rent_sponsor,
+    )]
+    pub swap_data: Account<'info, SwapAccount>,
+
+    /// The refundee of the atomic swap, receiving the escrowed lamports
+    #[account(mut, address = swap_data.current_refundee @ SwapError::InvalidRefundee)]
+    pub refundee: SystemAccount<'info>,
+
+    /// The submitter of this transaction, typically its fee payer, to whom the relayer tip is paid
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// CHECK: Rent sponsor's address for refunding PDA rent
+    #[account(mut, address = swap_data.rent_sponsor @ SwapError::InvalidRentSponsor)]
+    pub rent_sponsor: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InstantRefundNative<'info> {
+    /// The PDA holding the state information and the lamports of the atomic swap.
+    /// Will be closed upon successful execution and the resulting rent refund will be sent to the rent_sponsor.
+    #[account(
+        mut,
+        seeds = [
+            swap_data.mint.as_ref(),
+            swap_data.redeemer.as_ref(),
+            swap_data.refundee.as_ref(),
+            &swap_data.secret_hash,
+            &swap_data.swap_amount.to_le_bytes(),
+            &swap_data.timelock.to_le_bytes(),
+            &swap_data.salt.to_le_bytes(),
+        ],
+        bump = swap_data.bump,
+        constraint = swap_data.mint == NATIVE_MINT @ SwapError::NotNativeSwap,
+        close = rent_sponsor,
+    )]
+    pub swap_data: Account<'info, SwapAccount>,
 
-        Ok(())
-    }
-}
+    /// The refundee of the atomic swap, receiving the escrowed lamports
+    #[account(mut, address = swap_data.current_refundee @ SwapError::InvalidRefundee)]
+    pub refundee: SystemAccount<'info>,
 
-/// Stores the state information of the atomic swap on-chain
-#[account]
-#[derive(InitSpace)]
-pub struct SwapAccount {
-    /// The bump that derived this PDA.
-    /// Storing this makes later verifications less expensive.
-    pub bump: u8,
-    /// The exact slot after which (non-instant) refunds are allowed
-    pub expiry_slot: u64,
-    /// The bump associated with the identity pda.
-    /// This is needed by the program to authorize token transfers via the token vault.
-    pub identity_pda_bump: u8,
-    /// The entity that paid the rent fees for the creation of this PDA.
-    /// This will be referenced during the refund of the same upon closing this PDA.
-    pub rent_sponsor: Pubkey,
+    /// The redeemer of the atomic swap. They must sign this transaction.
+    #[account(address = swap_data.current_redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: Signer<'info>,
 
-    /// The mint for this atomic swap
-    pub mint: Pubkey,
-    /// The redeemer of the atomic swap
-    pub redeemer: Pubkey,
-    /// The refundee of the atomic swap
-    pub refundee: Pubkey,
-    /// The secret hash associated with the atomic swap
-    pub secret_hash: [u8; 32],
-    /// The quantity tokens to be transferred through this atomic swap
-    /// in base units of the token mint.  
-    /// E.g: A quantity of $1 represented by the token "USDC" with "6" decimals
-    /// must be provided as 1,000,000.
-    pub swap_amount: u64,
-    /// Represents the number of slots after which (non-instant) refunds are allowed
-    pub timelock: u64,
+    /// CHECK: Rent sponsor's address for PDA rent refund
+    #[account(mut, address = swap_data.rent_sponsor @ SwapError::InvalidRentSponsor)]
+    pub rent_sponsor: AccountInfo<'info>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-// The parameters must have the exact name and order as specified in the underlying function
-// to avoid "seed constraint violation" errors.
-// Refer: https://www.anchor-lang.com/docs/references/account-constraints#instruction-attribute
-#[instruction(redeemer: Pubkey, refundee: Pubkey, secret_hash: [u8; 32], swap_amount: u64, timelock: u64)]
-pub struct Initiate<'info> {
-    /// CHECK: Program-derived address used solely as signing authority (no data allocation)
+#[instruction(order: SwapOrder)]
+pub struct InitiateWithSignature<'info> {
+    /// CHECK: Program-derived address used solely as signing authority (no data allocation).
+    /// It must have been approved as a delegate of `owner_token_account` for at least `order.swap_amount`.
     #[account(seeds = [], bump)]
     pub identity_pda: AccountInfo<'info>,
 
     /// A PDA that maintains the on-chain state of the atomic swap throughout its lifecycle.
-    /// The choice of seeds is to make the already expensive possibility of frontrunning, more expensive.
+    /// The seeds are identical to that of `initiate()`.
     /// This PDA will be deleted upon completion of the swap.
     #[account(
         init,
         payer = rent_sponsor,
         seeds = [
             mint.key().as_ref(),
-            redeemer.as_ref(),
-            refundee.as_ref(),
-            &secret_hash,
-            &swap_amount.to_le_bytes(),
-            &timelock.to_le_bytes(),
+            order.redeemer.as_ref(),
+            order.refundee.as_ref(),
+            &order.secret_hash,
+            &order.swap_amount.to_le_bytes(),
+            &order.timelock.to_le_bytes(),
+            &order.salt.to_le_bytes(),
         ],
         bump,
         space = ANCHOR_DISCRIMINATOR + SwapAccount::INIT_SPACE,
     )]
     pub swap_data: Account<'info, SwapAccount>,
 
-    /// A permanent PDA that is controlled by the program through the `identity_pda`, as implied
-    /// by the value of the `authority` field below. As such, it serves as the "vault" by escrowing tokens
-    /// of type `mint` for the atomic swap.  
-    /// It is intended to be reused for all swaps involving the same mint.  
-    /// Just like `identity_pda`, it will be created during the first most invocation of `initiate()`
-    /// of every distinct mint using the `init_if_needed` attribute.
+    /// The token vault of `mint`, as in `initiate()`
     #[account(
         init_if_needed,
         payer = rent_sponsor,
@@ -305,38 +3191,75 @@ pub struct Initiate<'info> {
         bump,
         token::mint = mint,
         token::authority = identity_pda,
+        token::token_program = token_program,
     )]
-    pub token_vault: Account<'info, TokenAccount>,
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
 
-    /// The party that deposits the funds to be involved in the atomic swap.
-    /// They must sign this transaction.
-    pub funder: Signer<'info>,
+    /// The accounting of the shared token vault of `mint`, created alongside it
+    #[account(
+        init_if_needed,
+        payer = rent_sponsor,
+        seeds = [b"vault_state", mint.key().as_ref()],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + VaultState::INIT_SPACE,
+    )]
+    pub vault_state: Account<'info, VaultState>,
 
-    /// The token account of the funder
+    /// CHECK: The program configuration PDA, determining the protocol fee of this swap.
+    /// It may not have been created, in which case initiations are unrestricted and free of fees.
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    /// CHECK: The policy PDA of `mint`, which may not have been created.
+    /// Its contents are validated upon being loaded.
+    #[account(seeds = [b"mint_policy", mint.key().as_ref()], bump)]
+    pub mint_policy: UncheckedAccount<'info>,
+
+    /// CHECK: The owner of the funds, whose signature over `order` is verified via the Ed25519 program
+    pub owner: AccountInfo<'info>,
+
+    /// The token account of the owner, from which the funds are pulled by the `identity_pda` as delegate
     #[account(
         mut,
         token::mint = mint,
-        token::authority = funder,
+        token::authority = owner,
+        token::token_program = token_program,
     )]
-    pub funder_token_account: Account<'info, TokenAccount>,
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    /// The mint of the tokens involved in this swap. As this is a parameter, this program can thus be reused
-    /// for atomic swaps with different mints.
-    pub mint: Account<'info, Mint>,
+    /// A PDA tracking the nonce of the owner's next signed order.
+    /// It will be created during the owner's first signed initiation.
+    #[account(
+        init_if_needed,
+        payer = rent_sponsor,
+        seeds = [b"nonce", owner.key().as_ref()],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + SignerNonce::INIT_SPACE,
+    )]
+    pub signer_nonce: Account<'info, SignerNonce>,
 
-    /// Any entity that pays the PDA rent.
+    /// The mint of the tokens involved in this swap, as signed in the order
+    #[account(address = order.mint @ SwapError::InvalidMint, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Any entity that pays the PDA rent, typically the relayer submitting this transaction.
     /// Upon completion of the swap, the PDA rent refund resulting from the
     /// deletion of `swap_data` will be refunded to this address.
     #[account(mut)]
     pub rent_sponsor: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: The instructions sysvar, used to introspect the Ed25519 signature verification
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-pub struct Redeem<'info> {
-    /// CHECK: The Identity PDA, used only for authorizing token transfers, no data is read or written to it
+pub struct InstantRefundWithSignature<'info> {
+    /// CHECK: The Identity PDA, used solely for authorizing token transfers, no data is read or written to it
     #[account(seeds = [], bump = swap_data.identity_pda_bump)]
     pub identity_pda: AccountInfo<'info>,
 
@@ -351,35 +3274,77 @@ pub struct Redeem<'info> {
             &swap_data.secret_hash,
             &swap_data.swap_amount.to_le_bytes(),
             &swap_data.timelock.to_le_bytes(),
+            &swap_data.salt.to_le_bytes(),
         ],
         bump = swap_data.bump,
         close = rent_sponsor,
     )]
     pub swap_data: Account<'info, SwapAccount>,
 
-    /// A token account controlled by the program, escrowing the tokens for this atomic swap
-    #[account(mut, token::mint = swap_data.mint, token::authority = identity_pda)]
-    pub token_vault: Account<'info, TokenAccount>,
+    /// The mint of the tokens involved in this swap, needed for checked transfers.
+    /// It is writable, as transfer fees withheld in an isolated vault are harvested to it.
+    #[account(mut, address = swap_data.mint, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
 
-    /// CHECK: The token account of the redeemer
-    #[account(mut, token::mint = swap_data.mint, token::authority = swap_data.redeemer)]
-    pub redeemer_token_account: Account<'info, TokenAccount>,
+    /// A token account controlled by the program, escrowing the tokens for this atomic swap.
+    /// It is closed upon successful execution if it is isolated to this swap.
+    #[account(
+        mut,
+        address = swap_data.token_vault @ SwapError::InvalidTokenVault,
+        token::mint = mint,
+        token::authority = identity_pda,
+        token::token_program = token_program,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
 
-    /// CHECK: Rent sponsor's address for refunding PDA rent
+    /// The accounting of the shared token vault of the mint
+    #[account(
+        mut,
+        seeds = [b"vault_state", swap_data.mint.as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: The token account of the refundee
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = swap_data.current_refundee,
+        token::token_program = token_program,
+    )]
+    pub refundee_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Rent sponsor's address for PDA rent refund
     #[account(mut, address = swap_data.rent_sponsor @ SwapError::InvalidRentSponsor)]
     pub rent_sponsor: AccountInfo<'info>,
 
-    pub token_program: Program<'info, Token>,
+    /// A PDA tracking the nonce of the next intent signed by the current redeemer.
+    /// It will be created during their first signed order or intent.
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        seeds = [b"nonce", swap_data.current_redeemer.as_ref()],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + SignerNonce::INIT_SPACE,
+    )]
+    pub signer_nonce: Account<'info, SignerNonce>,
+
+    /// The submitter of this transaction, paying the rent of `signer_nonce` if it is created
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// CHECK: The instructions sysvar, used to introspect the Ed25519 signature verification
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-pub struct Refund<'info> {
-    /// CHECK: The Identity PDA, used solely for authorizing token transfers, no data is read or written to it
-    #[account(seeds = [], bump = swap_data.identity_pda_bump)]
-    pub identity_pda: AccountInfo<'info>,
-
-    /// The PDA holding the state information of the atomic swap. Will be closed upon successful execution
-    /// and the resulting rent refund will be sent to the rent_sponsor.
+pub struct UpdateRefundee<'info> {
+    /// The PDA holding the state information of the atomic swap
     #[account(
         mut,
         seeds = [
@@ -389,35 +3354,21 @@ pub struct Refund<'info> {
             &swap_data.secret_hash,
             &swap_data.swap_amount.to_le_bytes(),
             &swap_data.timelock.to_le_bytes(),
+            &swap_data.salt.to_le_bytes(),
         ],
         bump = swap_data.bump,
-        close = rent_sponsor,
     )]
     pub swap_data: Account<'info, SwapAccount>,
 
-    /// A token account controlled by the program, escrowing the tokens for this atomic swap
-    #[account(mut, token::mint = swap_data.mint, token::authority = identity_pda)]
-    pub token_vault: Account<'info, TokenAccount>,
-
-    /// CHECK: The token account of the refundee
-    #[account(mut, token::mint = swap_data.mint, token::authority = swap_data.refundee)]
-    pub refundee_token_account: Account<'info, TokenAccount>,
-
-    /// CHECK: Rent sponsor's address for refunding PDA rent
-    #[account(mut, address = swap_data.rent_sponsor @ SwapError::InvalidRentSponsor)]
-    pub rent_sponsor: AccountInfo<'info>,
-
-    pub token_program: Program<'info, Token>,
+    /// The current refundee of the atomic swap. They must sign this transaction.
+    #[account(address = swap_data.current_refundee @ SwapError::InvalidRefundee)]
+    pub refundee: Signer<'info>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-pub struct InstantRefund<'info> {
-    /// CHECK: The Identity PDA, used solely for authorizing token transfers, no data is read or written to it
-    #[account(seeds = [], bump = swap_data.identity_pda_bump)]
-    pub identity_pda: AccountInfo<'info>,
-
-    /// The PDA holding the state information of the atomic swap. Will be closed upon successful execution
-    /// and the resulting rent refund will be sent to the rent_sponsor.
+pub struct TransferRedeemer<'info> {
+    /// The PDA holding the state information of the atomic swap
     #[account(
         mut,
         seeds = [
@@ -427,70 +3378,255 @@ pub struct InstantRefund<'info> {
             &swap_data.secret_hash,
             &swap_data.swap_amount.to_le_bytes(),
             &swap_data.timelock.to_le_bytes(),
+            &swap_data.salt.to_le_bytes(),
         ],
         bump = swap_data.bump,
-        close = rent_sponsor,
     )]
     pub swap_data: Account<'info, SwapAccount>,
 
-    /// A token account controlled by the program, escrowing the tokens for this atomic swap
-    #[account(mut, token::mint = swap_data.mint, token::authority = identity_pda)]
-    pub token_vault: Account<'info, TokenAccount>,
+    /// The current redeemer of the atomic swap. They must sign this transaction.
+    #[account(address = swap_data.current_redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: Signer<'info>,
+}
 
-    /// CHECK: The token account of the refundee
-    #[account(mut, token::mint = swap_data.mint, token::authority = swap_data.refundee)]
-    pub refundee_token_account: Account<'info, TokenAccount>,
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    /// The program configuration, created once
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"config"],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + Config::INIT_SPACE,
+    )]
+    pub config: Account<'info, Config>,
 
-    /// The redeemer of the atomic swap. They must sign this transaction.
-    #[account(mut, address = swap_data.redeemer @ SwapError::InvalidRedeemer)]
-    pub redeemer: Signer<'info>,
+    /// This program, whose program data account holds its upgrade authority
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::SolanaSplSwaps>,
 
-    /// CHECK: Rent sponsor's address for PDA rent refund
-    #[account(mut, address = swap_data.rent_sponsor @ SwapError::InvalidRentSponsor)]
-    pub rent_sponsor: AccountInfo<'info>,
+    /// The program data account of this program
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ SwapError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    /// The upgrade authority of this program, who also pays the PDA rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    /// The program configuration
+    #[account(mut, seeds = [b"config"], bump = config.bump, has_one = admin @ SwapError::Unauthorized)]
+    pub config: Account<'info, Config>,
+
+    /// The admin of the program. They must sign this transaction.
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct SetMintPolicy<'info> {
+    /// The program configuration
+    #[account(seeds = [b"config"], bump = config.bump, has_one = admin @ SwapError::Unauthorized)]
+    pub config: Account<'info, Config>,
+
+    /// The policy PDA of `mint`. It will be created upon the first configuration of the mint.
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [b"mint_policy", mint.as_ref()],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + MintPolicy::INIT_SPACE,
+    )]
+    pub mint_policy: Account<'info, MintPolicy>,
+
+    /// The admin of the program, who also pays the PDA rent. They must sign this transaction.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    /// The program configuration
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = guardian @ SwapError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// The guardian of the program. They must sign this transaction.
+    pub guardian: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct SetMintPaused<'info> {
+    /// The program configuration
+    #[account(seeds = [b"config"], bump = config.bump, has_one = guardian @ SwapError::Unauthorized)]
+    pub config: Account<'info, Config>,
+
+    /// The policy PDA of `mint`. It will be created upon the first configuration of the mint.
+    #[account(
+        init_if_needed,
+        payer = guardian,
+        seeds = [b"mint_policy", mint.as_ref()],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + MintPolicy::INIT_SPACE,
+    )]
+    pub mint_policy: Account<'info, MintPolicy>,
+
+    /// The guardian of the program, who also pays the PDA rent. They must sign this transaction.
+    #[account(mut)]
+    pub guardian: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Reconcile<'info> {
+    /// The accounting of the token vault
+    #[account(seeds = [b"vault_state", vault_state.mint.as_ref()], bump = vault_state.bump)]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// The shared token vault of the mint
+    #[account(seeds = [vault_state.mint.as_ref()], bump)]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SweepSurplus<'info> {
+    /// CHECK: The Identity PDA, used solely for authorizing token transfers, no data is read or written to it
+    #[account(seeds = [], bump)]
+    pub identity_pda: AccountInfo<'info>,
 
-    pub token_program: Program<'info, Token>,
+    /// The program configuration
+    #[account(seeds = [b"config"], bump = config.bump, has_one = admin @ SwapError::Unauthorized)]
+    pub config: Account<'info, Config>,
+
+    /// The accounting of the token vault
+    #[account(seeds = [b"vault_state", mint.key().as_ref()], bump = vault_state.bump)]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// The shared token vault of the mint
+    #[account(
+        mut,
+        seeds = [mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = identity_pda,
+        token::token_program = token_program,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The mint of the token vault, needed for checked transfers
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The token account receiving the surplus
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The admin of the program. They must sign this transaction.
+    pub admin: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 /// Represents the initiated state of the swap where the funder has deposited funds into the vault
 #[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Initiated {
     pub mint: Pubkey,
     pub redeemer: Pubkey,
     pub refundee: Pubkey,
     pub secret_hash: [u8; 32],
+    pub hash_algorithm: HashAlgorithm,
+    /// The maximum length in bytes of the secret accepted upon redeem
+    pub max_secret_len: u16,
     /// The quantity of tokens transferred through this atomic swap in base units of the token mint.  
     /// E.g: A quantity of $1 represented by the token "USDC" with "6" decimals will be represented as 1,000,000.
     pub swap_amount: u64,
-    /// `timelock` represents the number of slots after which (non-instant) refunds are allowed
+    /// The quantity of tokens actually escrowed in the vault, i.e `swap_amount` net of any transfer fee
+    pub escrowed_amount: u64,
+    /// The quantity of tokens to be paid to the relayer submitting the redeem or refund
+    pub relayer_tip: u64,
+    /// `timelock` represents the number of slots or seconds after which (non-instant) refunds are allowed,
+    /// or the unix timestamp itself, as per `timelock_kind`
     pub timelock: u64,
+    /// The caller-supplied value distinguishing swaps with otherwise identical parameters
+    pub salt: u64,
+    /// The canonical identifier of this swap, on which all of its lifecycle events can be joined
+    pub order_id: [u8; 32],
+    pub timelock_kind: TimelockKind,
+    /// The exact slot or unix timestamp after which (non-instant) refunds are allowed
+    pub expiry: u64,
     /// Information regarding the destination chain in the atomic swap
     pub destination_data: Option<Vec<u8>>,
     pub funder: Pubkey,
 }
-/// Represents the redeemed state of the swap, where the redeemer has withdrawn funds from the vault
+/// Represents the redeemed state of the swap, where the redeemer has withdrawn funds from the vault.
+/// Like every lifecycle event, it is emitted through a self-CPI rather than logged, such that the revealed
+/// secret can be observed from the inner instructions of the transaction even if its logs are truncated.
 #[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Redeemed {
     pub mint: Pubkey,
     pub redeemer: Pubkey,
     pub refundee: Pubkey,
-    pub secret: [u8; 32],
+    /// The secret (preimage) revealed by the redeem, in full
+    pub secret: Vec<u8>,
     pub swap_amount: u64,
+    /// The protocol fee deducted from the escrowed amount, paid to the fee recipient
+    pub fee_amount: u64,
+    /// The quantity of tokens paid to the relayer that submitted the redeem
+    pub relayer_tip: u64,
     pub timelock: u64,
+    pub salt: u64,
+    pub order_id: [u8; 32],
+    /// The party the funds were paid to, i.e `redeemer` unless reassigned through `transfer_redeemer()`
+    pub current_redeemer: Pubkey,
+    /// The account credited with the funds: a token account, or `current_redeemer` for native SOL
+    pub destination: Pubkey,
 }
 /// Represents the refund state of the swap, where the initiator has withdrawn funds from the vault past expiry
 #[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Refunded {
     pub mint: Pubkey,
     pub redeemer: Pubkey,
     pub refundee: Pubkey,
     pub secret_hash: [u8; 32],
     pub swap_amount: u64,
+    /// The quantity of tokens paid to the relayer that submitted the refund
+    pub relayer_tip: u64,
     pub timelock: u64,
+    pub salt: u64,
+    pub order_id: [u8; 32],
+    /// The exact slot or unix timestamp after which the refund was allowed
+    pub expiry: u64,
+    /// The party the funds were returned to, i.e `refundee` unless rotated through `update_refundee()`
+    pub current_refundee: Pubkey,
+    /// The account credited with the funds: a token account, or `current_refundee` for native SOL
+    pub destination: Pubkey,
 }
 /// Represents the instant refund state of the swap, where the refundee has obtained
 /// a refund of the funds with the redeemer's consent
 #[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct InstantRefunded {
     pub mint: Pubkey,
     pub redeemer: Pubkey,
@@ -498,6 +3634,55 @@ pub struct InstantRefunded {
     pub secret_hash: [u8; 32],
     pub swap_amount: u64,
     pub timelock: u64,
+    pub salt: u64,
+    pub order_id: [u8; 32],
+    /// The party the funds were returned to, i.e `refundee` unless rotated through `update_refundee()`
+    pub current_refundee: Pubkey,
+    /// The account credited with the funds: a token account, or `current_refundee` for native SOL
+    pub destination: Pubkey,
+}
+
+/// Represents the reassignment of the beneficiary of a swap
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RedeemerTransferred {
+    pub order_id: [u8; 32],
+    pub previous_redeemer: Pubkey,
+    pub new_redeemer: Pubkey,
+}
+
+/// Represents the rotation of the party to which the funds of a swap are returned upon refund
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RefundeeUpdated {
+    pub order_id: [u8; 32],
+    pub previous_refundee: Pubkey,
+    pub new_refundee: Pubkey,
+}
+
+/// Represents the reconciliation of a shared token vault against the tokens owed to its open swaps
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reconciled {
+    pub mint: Pubkey,
+    /// The balance of the token vault
+    pub vault_balance: u64,
+    /// The tokens owed to the open swaps escrowed in the token vault
+    pub total_locked: u64,
+    pub open_swaps: u64,
+    /// The tokens in excess of `total_locked`, recoverable through `sweep_surplus()`
+    pub surplus: u64,
+    /// The tokens owed in excess of `vault_balance`, which should always be zero
+    pub deficit: u64,
+}
+
+/// Represents the recovery of the surplus of a shared token vault
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SurplusSwept {
+    pub mint: Pubkey,
+    pub destination_token_account: Pubkey,
+    pub amount: u64,
 }
 
 #[error_code]
@@ -513,4 +3698,103 @@ pub enum SwapError {
 
     #[msg("Attempt to refund before timelock expiry")]
     RefundBeforeExpiry,
+
+    #[msg("The provided refundee is not the original refundee of this swap")]
+    InvalidRefundee,
+
+    #[msg("The secret hash has non-zero bytes beyond the digest length of its hash algorithm")]
+    InvalidSecretHash,
+
+    #[msg("The maximum secret length must be non-zero and not exceed MAX_SECRET_LEN")]
+    InvalidMaxSecretLength,
+
+    #[msg("The provided secret exceeds the maximum secret length of this swap")]
+    SecretTooLong,
+
+    #[msg("The Ed25519 signature verification instruction is missing or does not match")]
+    InvalidSignature,
+
+    #[msg("The signed authorization has passed its deadline")]
+    SignatureExpired,
+
+    #[msg("The nonce of the signed order is not the next nonce of its signer")]
+    InvalidNonce,
+
+    #[msg("The provided mint is not the mint of the signed order")]
+    InvalidMint,
+
+    #[msg("This instruction is only applicable to atomic swaps of native SOL")]
+    NotNativeSwap,
+
+    #[msg("The signer is not authorized to perform this action")]
+    Unauthorized,
+
+    #[msg("The protocol fee must not exceed MAX_FEE_BPS")]
+    InvalidFeeBps,
+
+    #[msg("The provided fee recipient is not the fee recipient of this swap")]
+    InvalidFeeRecipient,
+
+    #[msg("The token account of the fee recipient is required for swaps with a protocol fee")]
+    FeeAccountRequired,
+
+    #[msg("The relayer tip exceeds the escrowed amount net of the protocol fee")]
+    RelayerTipTooLarge,
+
+    #[msg("The token account of the relayer is required for swaps with a relayer tip")]
+    RelayerAccountRequired,
+
+    #[msg("Initiations are paused")]
+    Paused,
+
+    #[msg("Initiations are paused for this mint")]
+    MintPaused,
+
+    #[msg("This mint is not on the allowlist")]
+    MintNotAllowed,
+
+    #[msg("Mints with a freeze authority are not allowed by the policy of this mint")]
+    FreezeAuthorityNotAllowed,
+
+    #[msg("Mints with a permanent delegate are not allowed by the policy of this mint")]
+    PermanentDelegateNotAllowed,
+
+    #[msg("The swap amount is below the minimum allowed for this mint")]
+    SwapAmountTooSmall,
+
+    #[msg("The swap amount is above the maximum allowed for this mint")]
+    SwapAmountTooLarge,
+
+    #[msg("The timelock is shorter than the minimum allowed")]
+    TimelockTooShort,
+
+    #[msg("The timelock is longer than the maximum allowed")]
+    TimelockTooLong,
+
+    #[msg("The timelock causes the expiry to overflow")]
+    TimelockOverflow,
+
+    #[msg("The swap amount must be non-zero, net of any transfer fee")]
+    ZeroSwapAmount,
+
+    #[msg("The minimum timelock must not exceed the maximum timelock")]
+    InvalidTimelockBounds,
+
+    #[msg("The provided token vault is not the token vault of this swap")]
+    InvalidTokenVault,
+
+    #[msg("The token vault holds no tokens in excess of those owed to open swaps")]
+    NoSurplus,
+
+    #[msg("The tokens locked in the token vault overflow")]
+    VaultAccountingOverflow,
+
+    #[msg("The swap releases more tokens than are locked in the token vault")]
+    VaultAccountingUnderflow,
+
+    #[msg("The balance of the token vault decreased during a deposit")]
+    VaultBalanceDecreased,
+
+    #[msg("The payout would leave its recipient below the rent-exempt minimum")]
+    PayoutNotRentExempt,
 }
@@ -4,8 +4,11 @@ import {
   workspace,
   web3,
   BN,
+  utils,
 } from "@coral-xyz/anchor";
 import * as spl from "@solana/spl-token";
+import { ripemd160 } from "@noble/hashes/ripemd160";
+import { keccak_256 } from "@noble/hashes/sha3";
 import { expect } from "chai";
 import crypto from "node:crypto";
 
@@ -15,35 +18,1492 @@ import { SolanaSplSwaps } from "../target/types/solana_spl_swaps";
 const provider = AnchorProvider.env();
 const connection = provider.connection;
 const program = workspace.SolanaSplSwaps as Program<SolanaSplSwaps>;
+type InitiateArgs = Parameters<typeof program.methods.initiate>;
 
-describe("Testing one way swap between Alice and Bob", () => {
-  const swapAmount = new BN(10);
+const U64_MAX = new BN("18446744073709551615");
+
+// Receives the protocol fees
+const feeRecipient = provider.wallet.publicKey;
+
+before(async () => {
+  // The program configuration is created once by the upgrade authority, i.e the provider's wallet,
+  // which also serves as the admin and the guardian
+  const [programData] = web3.PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    new web3.PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+  );
+  try {
+    await program.methods
+      .initializeConfig({
+        admin: provider.wallet.publicKey,
+        guardian: provider.wallet.publicKey,
+        feeRecipient,
+        feeBps: 0,
+        allowlistEnabled: false,
+        minTimelock: new BN(0),
+        maxTimelock: new BN(30 * 24 * 60 * 60), // 30 days
+      })
+      .accounts({ programData, authority: provider.wallet.publicKey })
+      .rpc();
+  } catch (_) {
+    console.log("Config already exists");
+  }
+});
+
+// Decodes the events emitted by the program through self-CPI in a transaction
+async function fetchEvents(signature: string) {
+  const latestBlockHash = await connection.getLatestBlockhash();
+  await connection.confirmTransaction(
+    { signature, ...latestBlockHash },
+    "confirmed"
+  );
+  const transaction = await connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  const accountKeys = transaction.transaction.message.getAccountKeys();
+  return transaction.meta.innerInstructions
+    .flatMap(({ instructions }) => instructions)
+    .filter(({ programIdIndex }) =>
+      accountKeys.get(programIdIndex).equals(program.programId)
+    )
+    .map(({ data }) => {
+      // Strips the event instruction tag preceding the event
+      const event = Buffer.from(utils.bytes.bs58.decode(data)).subarray(8);
+      return program.coder.events.decode(utils.bytes.base64.encode(event));
+    })
+    .filter((event) => event !== null);
+}
+
+// Overrides fields of the program configuration, the others being left as is
+async function updateConfig(
+  overrides: Partial<Parameters<typeof program.methods.updateConfig>[0]>
+) {
+  const [config] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("config")],
+    program.programId
+  );
+  const { bump, paused, ...params } = await program.account.config.fetch(
+    config
+  );
+  await program.methods
+    .updateConfig({ ...params, ...overrides })
+    .accounts({ admin: provider.wallet.publicKey })
+    .rpc();
+}
+
+// The unix timestamp of the cluster, by which wall-clock timelocks are measured
+async function clusterTime() {
+  const { data } = await connection.getAccountInfo(web3.SYSVAR_CLOCK_PUBKEY);
+  // Preceded by the slot, epoch start timestamp, epoch and leader schedule epoch
+  return Number(data.readBigInt64LE(32));
+}
+
+// Resolves once the cluster time is past the given unix timestamp
+async function awaitClusterTime(timestamp: number) {
+  while ((await clusterTime()) <= timestamp) {
+    await new Promise((r) => setTimeout(r, 500));
+  }
+}
+
+// A mint policy without any restriction, to be overridden in tests
+const permissiveMintPolicy = {
+  feeBps: null,
+  allowed: true,
+  minSwapAmount: null,
+  maxSwapAmount: null,
+  minTimelock: null,
+  maxTimelock: null,
+  rejectFreezeAuthority: false,
+  rejectPermanentDelegate: false,
+};
+
+async function setMintPolicy(
+  mint: web3.PublicKey,
+  overrides: Partial<typeof permissiveMintPolicy> = {}
+) {
+  await program.methods
+    .setMintPolicy(mint, { ...permissiveMintPolicy, ...overrides })
+    .accounts({ admin: provider.wallet.publicKey })
+    .rpc();
+}
+
+const tokenPrograms = [
+  { name: "Token", programId: spl.TOKEN_PROGRAM_ID, mintSeed: 33 },
+  { name: "Token-2022", programId: spl.TOKEN_2022_PROGRAM_ID, mintSeed: 34 },
+];
+
+for (const { name, programId: tokenProgram, mintSeed } of tokenPrograms) {
+  describe(`Testing one way swap between Alice and Bob (${name})`, () => {
+    const swapAmount = new BN(10);
+    const timelock = new BN(2); // 2 slots = 800 ms
+    const salt = new BN(crypto.randomBytes(8), "le");
+    const secret: Buffer = crypto.randomBytes(32);
+    const secretHash: Buffer = crypto
+      .createHash("sha256")
+      .update(secret)
+      .digest();
+    const destinationData = crypto.randomBytes(256); // can be null
+
+    const mint = web3.Keypair.fromSeed(new Uint8Array(32).fill(mintSeed));
+    const mintAuthority = web3.Keypair.fromSeed(new Uint8Array(32).fill(36));
+
+    // Alice, the initiator
+    const alice = new web3.Keypair();
+    let aliceTokenAccount: web3.PublicKey;
+
+    // Bob, the redeemer
+    const bob = new web3.Keypair();
+    let bobTokenAccount: web3.PublicKey;
+
+    // Sponsors the PDA rent and transaction fees
+    const rentSponsor = new web3.Keypair();
+
+    // Facilitates initiate on behalf
+    const funder = new web3.Keypair();
+    let funderTokenAccount: web3.PublicKey;
+
+    let feeTokenAccount: web3.PublicKey;
+
+    // The parameters of the swaps initiated by alice, to be overridden in tests
+    const defaultSwap = {
+      secretHash,
+      swapAmount,
+      timelock,
+      timelockKind: { slots: {} } as InitiateArgs[6],
+      hashAlgorithm: { sha256: {} } as InitiateArgs[7],
+      maxSecretLen: 32,
+      relayerTip: new BN(0),
+    };
+    type SwapParams = typeof defaultSwap;
+
+    function findSwapData(swap: SwapParams) {
+      return web3.PublicKey.findProgramAddressSync(
+        [
+          mint.publicKey.toBuffer(),
+          bob.publicKey.toBuffer(),
+          alice.publicKey.toBuffer(),
+          swap.secretHash,
+          swap.swapAmount.toArrayLike(Buffer, "le", 8),
+          swap.timelock.toArrayLike(Buffer, "le", 8),
+          salt.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )[0];
+    }
+
+    const swapData = findSwapData(defaultSwap);
+    const [tokenVault] = web3.PublicKey.findProgramAddressSync(
+      [mint.publicKey.toBuffer()],
+      program.programId
+    );
+
+    let latestBlockHash: web3.BlockhashWithExpiryBlockHeight;
+
+    before(async () => {
+      latestBlockHash = await connection.getLatestBlockhash();
+      // Fund rent sponsor with 1 SOL
+      const signature = await connection.requestAirdrop(
+        rentSponsor.publicKey,
+        web3.LAMPORTS_PER_SOL
+      );
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+
+      // Create Mint and Associated Token Accounts
+      try {
+        await spl.createMint(
+          connection,
+          rentSponsor,
+          mintAuthority.publicKey,
+          null,
+          0,
+          mint,
+          undefined,
+          tokenProgram
+        );
+      } catch (_) {
+        console.log("Mint already exists");
+      }
+      aliceTokenAccount = await spl.createAssociatedTokenAccount(
+        connection,
+        rentSponsor,
+        mint.publicKey,
+        alice.publicKey,
+        undefined,
+        tokenProgram
+      );
+      bobTokenAccount = await spl.createAssociatedTokenAccount(
+        connection,
+        rentSponsor,
+        mint.publicKey,
+        bob.publicKey,
+        undefined,
+        tokenProgram
+      );
+      funderTokenAccount = await spl.createAssociatedTokenAccount(
+        connection,
+        rentSponsor,
+        mint.publicKey,
+        funder.publicKey,
+        undefined,
+        tokenProgram
+      );
+      feeTokenAccount = (
+        await spl.getOrCreateAssociatedTokenAccount(
+          connection,
+          rentSponsor,
+          mint.publicKey,
+          feeRecipient,
+          undefined,
+          undefined,
+          undefined,
+          tokenProgram
+        )
+      ).address;
+
+      // Fund alice's token acc with tokens
+      await spl.mintTo(
+        connection,
+        rentSponsor,
+        mint.publicKey,
+        aliceTokenAccount,
+        mintAuthority,
+        swapAmount.toNumber() * 20,
+        [],
+        undefined,
+        tokenProgram
+      );
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+
+      // Fund funder's token acc with tokens
+      await spl.mintTo(
+        connection,
+        rentSponsor,
+        mint.publicKey,
+        funderTokenAccount,
+        mintAuthority,
+        swapAmount.toNumber() * 10,
+        [],
+        undefined,
+        tokenProgram
+      );
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+
+      console.log(
+        `Account Information:
+  Alice     : ${alice.publicKey}\tAlice TokenAcc:\t${aliceTokenAccount}
+  Bob       : ${bob.publicKey}\tBob TokenAcc:\t${bobTokenAccount}
+  Swap Data : ${swapData}\tToken Vault:\t${tokenVault}
+  Mint      : ${mint.publicKey}\tMint Authority:\t${mintAuthority.publicKey}
+  Sponsor   : ${rentSponsor.publicKey}\n`
+      );
+    });
+
+    // Initiates a swap from alice to bob, returning its swap data PDA
+    async function aliceInitiateSwap(overrides: Partial<SwapParams> = {}) {
+      const swap = { ...defaultSwap, ...overrides };
+      const signature = await program.methods
+        .initiate(
+          bob.publicKey,
+          alice.publicKey,
+          [...swap.secretHash],
+          swap.swapAmount,
+          swap.timelock,
+          salt,
+          swap.timelockKind,
+          swap.hashAlgorithm,
+          swap.maxSecretLen,
+          swap.relayerTip,
+          destinationData
+        )
+        .accounts({
+          funder: alice.publicKey,
+          funderTokenAccount: aliceTokenAccount,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          tokenProgram,
+        })
+        .signers([alice, rentSponsor])
+        .rpc();
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+      console.log(`\tInitiate: \t${signature}`);
+      return findSwapData(swap);
+    }
+
+    async function aliceInitiate(relayerTip = new BN(0)) {
+      await aliceInitiateSwap({ relayerTip });
+    }
+
+    // Redeems the given swap to bob's token account
+    async function bobRedeemSwap(swap: web3.PublicKey, swapSecret: Buffer) {
+      const signature = await program.methods
+        .redeem(swapSecret)
+        .accounts({
+          redeemerTokenAccount: bobTokenAccount,
+          feeTokenAccount: null,
+          relayer: provider.wallet.publicKey,
+          relayerTokenAccount: null,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          swapData: swap,
+          tokenVault,
+          tokenProgram,
+        })
+        .rpc();
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+      return signature;
+    }
+
+    it("Test initiate on behalf", async () => {
+      const aliceBalanceBefore = (
+        await connection.getTokenAccountBalance(aliceTokenAccount)
+      ).value.uiAmount;
+      const funderPreBalance = (
+        await connection.getTokenAccountBalance(funderTokenAccount)
+      ).value.uiAmount;
+
+      const signature = await program.methods
+        .initiate(
+          bob.publicKey,
+          alice.publicKey,
+          [...secretHash],
+          swapAmount,
+          timelock,
+          salt,
+          { slots: {} },
+          { sha256: {} },
+          32,
+          new BN(0),
+          destinationData
+        )
+        .accounts({
+          funder: funder.publicKey,
+          funderTokenAccount,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          tokenProgram,
+        })
+        .signers([funder, rentSponsor])
+        .rpc();
+      console.log(`\tFunder initiated on behalf of alice: \t${signature}`);
+
+      const aliceBalance = (
+        await connection.getTokenAccountBalance(aliceTokenAccount)
+      ).value.uiAmount;
+      expect(aliceBalance).to.equal(aliceBalanceBefore);
+
+      const funderPostBalance = (
+        await connection.getTokenAccountBalance(funderTokenAccount)
+      ).value.uiAmount;
+      expect(funderPostBalance).to.equal(
+        funderPreBalance - swapAmount.toNumber()
+      );
+    });
+
+    it("Test redeem", async () => {
+      // The previous testcase has initiated the swap
+      const bobBalanceBefore = (
+        await connection.getTokenAccountBalance(bobTokenAccount)
+      ).value.uiAmount;
+      const signature = await program.methods
+        .redeem(secret)
+        .accounts({
+          redeemerTokenAccount: bobTokenAccount,
+          feeTokenAccount: null,
+          relayer: provider.wallet.publicKey,
+          relayerTokenAccount: null,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          swapData,
+          tokenVault,
+          tokenProgram,
+        })
+        .rpc();
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+      console.log(`\tRedeem: \t${signature}`);
+
+      const bobBalance = (
+        await connection.getTokenAccountBalance(bobTokenAccount)
+      ).value.uiAmount;
+      expect(bobBalance - bobBalanceBefore).to.equal(swapAmount.toNumber());
+    });
+
+    it("Test redeem with protocol fee", async () => {
+      // A fee of 10% for this mint, overriding the global fee of 0
+      await setMintPolicy(mint.publicKey, { feeBps: 1_000 });
+      await aliceInitiate();
+      await setMintPolicy(mint.publicKey);
+
+      const bobBalanceBefore = (
+        await connection.getTokenAccountBalance(bobTokenAccount)
+      ).value.uiAmount;
+      const feeBalanceBefore = (
+        await connection.getTokenAccountBalance(feeTokenAccount)
+      ).value.uiAmount;
+      const signature = await program.methods
+        .redeem(secret)
+        .accounts({
+          redeemerTokenAccount: bobTokenAccount,
+          feeTokenAccount,
+          relayer: provider.wallet.publicKey,
+          relayerTokenAccount: null,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          swapData,
+          tokenVault,
+          tokenProgram,
+        })
+        .rpc();
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+      console.log(`\tRedeem With Fee: \t${signature}`);
+
+      // The fee was determined at initiation, hence unaffected by the later reset
+      const fee = swapAmount.toNumber() / 10;
+      const bobBalance = (
+        await connection.getTokenAccountBalance(bobTokenAccount)
+      ).value.uiAmount;
+      expect(bobBalance - bobBalanceBefore).to.equal(
+        swapAmount.toNumber() - fee
+      );
+      const feeBalance = (
+        await connection.getTokenAccountBalance(feeTokenAccount)
+      ).value.uiAmount;
+      expect(feeBalance - feeBalanceBefore).to.equal(fee);
+    });
+
+    it("Test redeem after the configuration changes", async () => {
+      await setMintPolicy(mint.publicKey, { feeBps: 1_000 });
+      await aliceInitiate();
+      await setMintPolicy(mint.publicKey);
+
+      // Neither pausing nor rotating the fee recipient affects open swaps
+      const guardian = provider.wallet.publicKey;
+      await updateConfig({ feeRecipient: bob.publicKey });
+      await program.methods.setPaused(true).accounts({ guardian }).rpc();
+      const feeBalanceBefore = (
+        await connection.getTokenAccountBalance(feeTokenAccount)
+      ).value.uiAmount;
+      try {
+        const signature = await program.methods
+          .redeem(secret)
+          .accounts({
+            redeemerTokenAccount: bobTokenAccount,
+            feeTokenAccount,
+            relayer: provider.wallet.publicKey,
+            relayerTokenAccount: null,
+            mint: mint.publicKey,
+            rentSponsor: rentSponsor.publicKey,
+            swapData,
+            tokenVault,
+            tokenProgram,
+          })
+          .rpc();
+        await connection.confirmTransaction({ signature, ...latestBlockHash });
+        console.log(`\tRedeem While Paused: \t${signature}`);
+      } finally {
+        await program.methods.setPaused(false).accounts({ guardian }).rpc();
+        await updateConfig({ feeRecipient });
+      }
+
+      const feeBalance = (
+        await connection.getTokenAccountBalance(feeTokenAccount)
+      ).value.uiAmount;
+      expect(feeBalance - feeBalanceBefore).to.equal(
+        swapAmount.toNumber() / 10
+      );
+    });
+
+    it("Test redeem with associated token account creation", async () => {
+      await aliceInitiate();
+      const bobBalanceBefore = (
+        await connection.getTokenAccountBalance(bobTokenAccount)
+      ).value.uiAmount;
+      // Bob's associated token account already exists, hence it is left as is
+      const signature = await program.methods
+        .redeemWithAta(secret)
+        .accounts({
+          redeemer: bob.publicKey,
+          feeTokenAccount: null,
+          relayer: provider.wallet.publicKey,
+          relayerTokenAccount: null,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          swapData,
+          tokenVault,
+          tokenProgram,
+        })
+        .rpc();
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+      console.log(`\tRedeem With ATA: \t${signature}`);
+
+      const bobBalance = (
+        await connection.getTokenAccountBalance(bobTokenAccount)
+      ).value.uiAmount;
+      expect(bobBalance - bobBalanceBefore).to.equal(swapAmount.toNumber());
+    });
+
+    // A new key, whose associated token account does not exist yet
+    function newKeyWithoutTokenAccount() {
+      const key = new web3.Keypair();
+      const tokenAccount = spl.getAssociatedTokenAddressSync(
+        mint.publicKey,
+        key.publicKey,
+        false,
+        tokenProgram
+      );
+      return { key, tokenAccount };
+    }
+
+    it("Test redeem creating the associated token account", async () => {
+      await aliceInitiate();
+      const carol = newKeyWithoutTokenAccount();
+      await program.methods
+        .transferRedeemer(carol.key.publicKey)
+        .accounts({ swapData, redeemer: bob.publicKey })
+        .signers([bob])
+        .rpc();
+
+      const signature = await program.methods
+        .redeemWithAta(secret)
+        .accounts({
+          redeemer: carol.key.publicKey,
+          feeTokenAccount: null,
+          relayer: provider.wallet.publicKey,
+          relayerTokenAccount: null,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          swapData,
+          tokenVault,
+          tokenProgram,
+        })
+        .rpc();
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+      console.log(`\tRedeem Creating ATA: \t${signature}`);
+
+      const carolBalance = (
+        await connection.getTokenAccountBalance(carol.tokenAccount)
+      ).value.uiAmount;
+      expect(carolBalance).to.equal(swapAmount.toNumber());
+    });
+
+    it("Test redeem to a chosen destination", async () => {
+      await aliceInitiate();
+      // Bob directs the payout to the funder's token account
+      const funderBalanceBefore = (
+        await connection.getTokenAccountBalance(funderTokenAccount)
+      ).value.uiAmount;
+      const signature = await program.methods
+        .redeemTo(secret)
+        .accounts({
+          redeemer: bob.publicKey,
+          destinationTokenAccount: funderTokenAccount,
+          feeTokenAccount: null,
+          relayer: provider.wallet.publicKey,
+          relayerTokenAccount: null,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          swapData,
+          tokenVault,
+          tokenProgram,
+        })
+        .signers([bob])
+        .rpc();
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+      console.log(`\tRedeem To: \t${signature}`);
+
+      const funderBalance = (
+        await connection.getTokenAccountBalance(funderTokenAccount)
+      ).value.uiAmount;
+      expect(funderBalance - funderBalanceBefore).to.equal(
+        swapAmount.toNumber()
+      );
+    });
+
+    it("Test redeem after transferring the redeemer", async () => {
+      await aliceInitiate();
+      // Bob hands the swap over to the funder's key
+      await program.methods
+        .transferRedeemer(funder.publicKey)
+        .accounts({ swapData, redeemer: bob.publicKey })
+        .signers([bob])
+        .rpc();
+
+      const funderBalanceBefore = (
+        await connection.getTokenAccountBalance(funderTokenAccount)
+      ).value.uiAmount;
+      const signature = await program.methods
+        .redeem(secret)
+        .accounts({
+          redeemerTokenAccount: funderTokenAccount,
+          feeTokenAccount: null,
+          relayer: provider.wallet.publicKey,
+          relayerTokenAccount: null,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          swapData,
+          tokenVault,
+          tokenProgram,
+        })
+        .rpc();
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+      console.log(`\tRedeem Transferred: \t${signature}`);
+
+      // The payout is attributed to the current redeemer rather than to bob
+      const [event] = await fetchEvents(signature);
+      expect(event.name).to.equal("redeemed");
+      expect(event.data.redeemer.equals(bob.publicKey)).to.be.true;
+      expect(event.data.currentRedeemer.equals(funder.publicKey)).to.be.true;
+      expect(event.data.destination.equals(funderTokenAccount)).to.be.true;
+
+      const funderBalance = (
+        await connection.getTokenAccountBalance(funderTokenAccount)
+      ).value.uiAmount;
+      expect(funderBalance - funderBalanceBefore).to.equal(
+        swapAmount.toNumber()
+      );
+    });
+
+    it("Test order id", async () => {
+      await aliceInitiate();
+      // The order id is the SHA-256 hash of the chain id, the program id
+      // and the parameters of the swap
+      const orderId = crypto
+        .createHash("sha256")
+        .update(Buffer.from("solana"))
+        .update(program.programId.toBuffer())
+        .update(mint.publicKey.toBuffer())
+        .update(bob.publicKey.toBuffer())
+        .update(alice.publicKey.toBuffer())
+        .update(secretHash)
+        .update(swapAmount.toArrayLike(Buffer, "le", 8))
+        .update(timelock.toArrayLike(Buffer, "le", 8))
+        .update(salt.toArrayLike(Buffer, "le", 8))
+        .digest();
+      const swap = await program.account.swapAccount.fetch(swapData);
+      expect(Buffer.from(swap.orderId).equals(orderId)).to.be.true;
+
+      const signature = await bobRedeemSwap(swapData, secret);
+      const [event] = await fetchEvents(signature);
+      expect(event.name).to.equal("redeemed");
+      expect(Buffer.from(event.data.orderId).equals(orderId)).to.be.true;
+    });
+
+    async function expectInitiateToFail(
+      code: string,
+      overrides: Partial<SwapParams> = {}
+    ) {
+      try {
+        await aliceInitiateSwap(overrides);
+        expect.fail(`Initiate should fail with ${code}`);
+      } catch (e) {
+        expect(e.error?.errorCode?.code).to.equal(code);
+      }
+    }
+
+    it("Test initiate while paused", async () => {
+      const guardian = provider.wallet.publicKey;
+
+      await program.methods.setPaused(true).accounts({ guardian }).rpc();
+      await expectInitiateToFail("Paused");
+      await program.methods.setPaused(false).accounts({ guardian }).rpc();
+
+      await program.methods
+        .setMintPaused(mint.publicKey, true)
+        .accounts({ guardian })
+        .rpc();
+      await expectInitiateToFail("MintPaused");
+      await program.methods
+        .setMintPaused(mint.publicKey, false)
+        .accounts({ guardian })
+        .rpc();
+    });
+
+    it("Test initiate against the mint policy", async () => {
+      await setMintPolicy(mint.publicKey, {
+        maxSwapAmount: swapAmount.subn(1),
+      });
+      await expectInitiateToFail("SwapAmountTooLarge");
+
+      // 2 slots are approximated as 1 second
+      await setMintPolicy(mint.publicKey, { minTimelock: new BN(2) });
+      await expectInitiateToFail("TimelockTooShort");
+
+      await setMintPolicy(mint.publicKey);
+    });
+
+    it("Test initiate with an invalid amount or timelock", async () => {
+      await expectInitiateToFail("ZeroSwapAmount", { swapAmount: new BN(0) });
+
+      // Beyond the global maximum of 30 days
+      await expectInitiateToFail("TimelockTooLong", {
+        timelock: new BN(31 * 24 * 60 * 60),
+        timelockKind: { seconds: {} },
+      });
+
+      // Within the maximum of the mint policy, but the expiry is not representable
+      await setMintPolicy(mint.publicKey, { maxTimelock: U64_MAX });
+      await expectInitiateToFail("TimelockOverflow", {
+        timelock: U64_MAX,
+        timelockKind: { seconds: {} },
+      });
+      await setMintPolicy(mint.publicKey);
+    });
+
+    it("Test redeem with a Keccak-256 hashlock", async () => {
+      const keccakSecret = crypto.randomBytes(32);
+      const keccakSwap = await aliceInitiateSwap({
+        secretHash: Buffer.from(keccak_256(keccakSecret)),
+        hashAlgorithm: { keccak256: {} },
+      });
+      const bobBalanceBefore = (
+        await connection.getTokenAccountBalance(bobTokenAccount)
+      ).value.uiAmount;
+      const signature = await bobRedeemSwap(keccakSwap, keccakSecret);
+      console.log(`\tRedeem Keccak-256: \t${signature}`);
+
+      const bobBalance = (
+        await connection.getTokenAccountBalance(bobTokenAccount)
+      ).value.uiAmount;
+      expect(bobBalance - bobBalanceBefore).to.equal(swapAmount.toNumber());
+    });
+
+    it("Test redeem with a Hash160 hashlock", async () => {
+      const hash160Secret = crypto.randomBytes(32);
+      // The 20 byte digest is zero-padded to 32 bytes
+      const hash160 = Buffer.alloc(32);
+      hash160.set(
+        ripemd160(crypto.createHash("sha256").update(hash160Secret).digest())
+      );
+      const hash160Swap = await aliceInitiateSwap({
+        secretHash: hash160,
+        hashAlgorithm: { hash160: {} },
+      });
+      const bobBalanceBefore = (
+        await connection.getTokenAccountBalance(bobTokenAccount)
+      ).value.uiAmount;
+      const signature = await bobRedeemSwap(hash160Swap, hash160Secret);
+      console.log(`\tRedeem Hash160: \t${signature}`);
+
+      const bobBalance = (
+        await connection.getTokenAccountBalance(bobTokenAccount)
+      ).value.uiAmount;
+      expect(bobBalance - bobBalanceBefore).to.equal(swapAmount.toNumber());
+    });
+
+    it("Test initiate with a Hash160 hash of non-zero padding", async () => {
+      // A SHA-256 hash does not leave the 12 trailing bytes zero
+      await expectInitiateToFail("InvalidSecretHash", {
+        hashAlgorithm: { hash160: {} },
+      });
+    });
+
+    async function expectRedeemToFail(
+      code: string,
+      swap: web3.PublicKey,
+      swapSecret: Buffer
+    ) {
+      try {
+        await bobRedeemSwap(swap, swapSecret);
+        expect.fail(`Redeem should fail with ${code}`);
+      } catch (e) {
+        expect(e.error?.errorCode?.code).to.equal(code);
+      }
+    }
+
+    // Instantly refunds the given swap to alice's token account
+    async function bobInstantRefundSwap(swap: web3.PublicKey) {
+      await program.methods
+        .instantRefund()
+        .accounts({
+          refundeeTokenAccount: aliceTokenAccount,
+          redeemer: bob.publicKey,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          swapData: swap,
+          tokenVault,
+          tokenProgram,
+        })
+        .signers([bob])
+        .rpc();
+    }
+
+    it("Test redeem with secrets of other lengths than 32 bytes", async () => {
+      // A secret shorter than 32 bytes, and one of the maximum length
+      for (const length of [20, 512]) {
+        const otherSecret = crypto.randomBytes(length);
+        const otherSwap = await aliceInitiateSwap({
+          secretHash: crypto.createHash("sha256").update(otherSecret).digest(),
+          maxSecretLen: length,
+        });
+        const bobBalanceBefore = (
+          await connection.getTokenAccountBalance(bobTokenAccount)
+        ).value.uiAmount;
+        const signature = await bobRedeemSwap(otherSwap, otherSecret);
+        console.log(`\tRedeem ${length} Byte Secret: \t${signature}`);
+
+        const bobBalance = (
+          await connection.getTokenAccountBalance(bobTokenAccount)
+        ).value.uiAmount;
+        expect(bobBalance - bobBalanceBefore).to.equal(swapAmount.toNumber());
+      }
+    });
+
+    it("Test redeem with a secret longer than the maximum", async () => {
+      // The secret hashes to the secret hash, but exceeds the maximum length
+      // of the swap, respectively of MAX_SECRET_LEN
+      for (const maxSecretLen of [32, 512]) {
+        const longSecret = crypto.randomBytes(maxSecretLen + 1);
+        const longSwap = await aliceInitiateSwap({
+          secretHash: crypto.createHash("sha256").update(longSecret).digest(),
+          maxSecretLen,
+        });
+        await expectRedeemToFail("SecretTooLong", longSwap, longSecret);
+        await bobInstantRefundSwap(longSwap);
+      }
+
+      // MAX_SECRET_LEN is 512 bytes
+      await expectInitiateToFail("InvalidMaxSecretLength", {
+        maxSecretLen: 513,
+      });
+    });
+
+    // Initiates the default swap from alice to bob in a token vault isolated to it
+    async function aliceInitiateIsolated() {
+      const [isolatedVault] = web3.PublicKey.findProgramAddressSync(
+        [swapData.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .initiateIsolated(
+          bob.publicKey,
+          alice.publicKey,
+          [...secretHash],
+          swapAmount,
+          timelock,
+          salt,
+          { slots: {} },
+          { sha256: {} },
+          32,
+          new BN(0),
+          null
+        )
+        .accounts({
+          funder: alice.publicKey,
+          funderTokenAccount: aliceTokenAccount,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          tokenProgram,
+        })
+        .signers([alice, rentSponsor])
+        .rpc();
+      return isolatedVault;
+    }
+
+    async function bobRedeemIsolated(isolatedVault: web3.PublicKey) {
+      const signature = await program.methods
+        .redeem(secret)
+        .accounts({
+          redeemerTokenAccount: bobTokenAccount,
+          feeTokenAccount: null,
+          relayer: provider.wallet.publicKey,
+          relayerTokenAccount: null,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          swapData,
+          tokenVault: isolatedVault,
+          tokenProgram,
+        })
+        .rpc();
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+      return signature;
+    }
+
+    it("Test redeem from an isolated vault", async () => {
+      const isolatedVault = await aliceInitiateIsolated();
+      const { tokenVault: swapTokenVault, vaultIsolated } =
+        await program.account.swapAccount.fetch(swapData);
+      expect(swapTokenVault.equals(isolatedVault)).to.be.true;
+      expect(vaultIsolated).to.be.true;
+
+      const bobBalanceBefore = (
+        await connection.getTokenAccountBalance(bobTokenAccount)
+      ).value.uiAmount;
+      const signature = await bobRedeemIsolated(isolatedVault);
+      console.log(`\tRedeem Isolated: \t${signature}`);
+
+      const bobBalance = (
+        await connection.getTokenAccountBalance(bobTokenAccount)
+      ).value.uiAmount;
+      expect(bobBalance - bobBalanceBefore).to.equal(swapAmount.toNumber());
+      // The isolated vault is closed alongside the swap
+      expect(await connection.getAccountInfo(isolatedVault)).to.be.null;
+    });
+
+    it("Test redeem from an isolated vault holding extra tokens", async () => {
+      const isolatedVault = await aliceInitiateIsolated();
+      // Anyone can send tokens to the vault, which must not prevent its closing
+      const extraAmount = 3;
+      await spl.mintTo(
+        connection,
+        rentSponsor,
+        mint.publicKey,
+        isolatedVault,
+        mintAuthority,
+        extraAmount,
+        [],
+        undefined,
+        tokenProgram
+      );
+
+      const bobBalanceBefore = (
+        await connection.getTokenAccountBalance(bobTokenAccount)
+      ).value.uiAmount;
+      const signature = await bobRedeemIsolated(isolatedVault);
+      console.log(`\tRedeem Isolated With Extra: \t${signature}`);
+
+      // The extra tokens are paid out alongside the swap amount
+      const bobBalance = (
+        await connection.getTokenAccountBalance(bobTokenAccount)
+      ).value.uiAmount;
+      expect(bobBalance - bobBalanceBefore).to.equal(
+        swapAmount.toNumber() + extraAmount
+      );
+      expect(await connection.getAccountInfo(isolatedVault)).to.be.null;
+    });
+
+    it("Test refund", async () => {
+      await aliceInitiate(); // Re-initiating for this test
+      const timelockMs = timelock.toNumber() * 400;
+      console.log(`Awaiting timelock of ${timelockMs}ms for Refund`);
+      await new Promise((r) => setTimeout(r, timelockMs + 1000)); // Add an extra sec
+      const aliceBalanceBefore = (
+        await connection.getTokenAccountBalance(aliceTokenAccount)
+      ).value.uiAmount;
+      const signature = await program.methods
+        .refund()
+        .accounts({
+          refundeeTokenAccount: aliceTokenAccount,
+          relayer: provider.wallet.publicKey,
+          relayerTokenAccount: null,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          swapData,
+          tokenVault,
+          tokenProgram,
+        })
+        .rpc();
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+      console.log(`\tRefund: \t${signature}`);
+
+      const aliceBalance = (
+        await connection.getTokenAccountBalance(aliceTokenAccount)
+      ).value.uiAmount;
+      expect(aliceBalance - aliceBalanceBefore).to.equal(swapAmount.toNumber());
+    });
+
+    it("Test refund to a rotated refundee", async () => {
+      await aliceInitiate(); // Re-initiating for this test
+      // Alice hands the refund over to the funder's key
+      await program.methods
+        .updateRefundee(funder.publicKey)
+        .accounts({ swapData, refundee: alice.publicKey })
+        .signers([alice])
+        .rpc();
+      const { currentRefundee } = await program.account.swapAccount.fetch(
+        swapData
+      );
+      expect(currentRefundee.toBase58()).to.equal(funder.publicKey.toBase58());
+
+      const timelockMs = timelock.toNumber() * 400;
+      console.log(`Awaiting timelock of ${timelockMs}ms for Refund To`);
+      await new Promise((r) => setTimeout(r, timelockMs + 1000)); // Add an extra sec
+      const funderBalanceBefore = (
+        await connection.getTokenAccountBalance(funderTokenAccount)
+      ).value.uiAmount;
+      const signature = await program.methods
+        .refundTo()
+        .accounts({
+          refundee: funder.publicKey,
+          destinationTokenAccount: funderTokenAccount,
+          relayer: provider.wallet.publicKey,
+          relayerTokenAccount: null,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          swapData,
+          tokenVault,
+          tokenProgram,
+        })
+        .signers([funder])
+        .rpc();
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+      console.log(`\tRefund To: \t${signature}`);
+
+      const funderBalance = (
+        await connection.getTokenAccountBalance(funderTokenAccount)
+      ).value.uiAmount;
+      expect(funderBalance - funderBalanceBefore).to.equal(
+        swapAmount.toNumber()
+      );
+    });
+
+    it("Test refund with relayer tip", async () => {
+      const relayerTip = new BN(1);
+      await aliceInitiate(relayerTip);
+      const timelockMs = timelock.toNumber() * 400;
+      console.log(`Awaiting timelock of ${timelockMs}ms for Refund`);
+      await new Promise((r) => setTimeout(r, timelockMs + 1000)); // Add an extra sec
+      const aliceBalanceBefore = (
+        await connection.getTokenAccountBalance(aliceTokenAccount)
+      ).value.uiAmount;
+      const funderBalanceBefore = (
+        await connection.getTokenAccountBalance(funderTokenAccount)
+      ).value.uiAmount;
+
+      // The funder cranks the refund on alice's behalf
+      const signature = await program.methods
+        .refund()
+        .accounts({
+          refundeeTokenAccount: aliceTokenAccount,
+          relayer: funder.publicKey,
+          relayerTokenAccount: funderTokenAccount,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          swapData,
+          tokenVault,
+          tokenProgram,
+        })
+        .signers([funder])
+        .rpc();
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+      console.log(`\tRefund With Tip: \t${signature}`);
+
+      const aliceBalance = (
+        await connection.getTokenAccountBalance(aliceTokenAccount)
+      ).value.uiAmount;
+      expect(aliceBalance - aliceBalanceBefore).to.equal(
+        swapAmount.toNumber() - relayerTip.toNumber()
+      );
+      const funderBalance = (
+        await connection.getTokenAccountBalance(funderTokenAccount)
+      ).value.uiAmount;
+      expect(funderBalance - funderBalanceBefore).to.equal(
+        relayerTip.toNumber()
+      );
+    });
+
+    it("Test refund creating the associated token account", async () => {
+      await aliceInitiate(); // Re-initiating for this test
+      const carol = newKeyWithoutTokenAccount();
+      await program.methods
+        .updateRefundee(carol.key.publicKey)
+        .accounts({ swapData, refundee: alice.publicKey })
+        .signers([alice])
+        .rpc();
+
+      const timelockMs = timelock.toNumber() * 400;
+      console.log(`Awaiting timelock of ${timelockMs}ms for Refund With ATA`);
+      await new Promise((r) => setTimeout(r, timelockMs + 1000)); // Add an extra sec
+      const signature = await program.methods
+        .refundWithAta()
+        .accounts({
+          refundee: carol.key.publicKey,
+          relayer: provider.wallet.publicKey,
+          relayerTokenAccount: null,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          swapData,
+          tokenVault,
+          tokenProgram,
+        })
+        .rpc();
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+      console.log(`\tRefund Creating ATA: \t${signature}`);
+
+      const carolBalance = (
+        await connection.getTokenAccountBalance(carol.tokenAccount)
+      ).value.uiAmount;
+      expect(carolBalance).to.equal(swapAmount.toNumber());
+    });
+
+    async function aliceRefundSwap(swap: web3.PublicKey) {
+      const signature = await program.methods
+        .refund()
+        .accounts({
+          refundeeTokenAccount: aliceTokenAccount,
+          relayer: provider.wallet.publicKey,
+          relayerTokenAccount: null,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          swapData: swap,
+          tokenVault,
+          tokenProgram,
+        })
+        .rpc();
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+      return signature;
+    }
+
+    // Refunds the given swap once it has expired, failing before then
+    async function aliceRefundAtExpiry(swap: web3.PublicKey, expiry: number) {
+      try {
+        await aliceRefundSwap(swap);
+        expect.fail("Refund should fail before the expiry");
+      } catch (e) {
+        expect(e.error?.errorCode?.code).to.equal("RefundBeforeExpiry");
+      }
+
+      await awaitClusterTime(expiry);
+      const aliceBalanceBefore = (
+        await connection.getTokenAccountBalance(aliceTokenAccount)
+      ).value.uiAmount;
+      const signature = await aliceRefundSwap(swap);
+      console.log(`\tRefund At Expiry: \t${signature}`);
+
+      const aliceBalance = (
+        await connection.getTokenAccountBalance(aliceTokenAccount)
+      ).value.uiAmount;
+      expect(aliceBalance - aliceBalanceBefore).to.equal(swapAmount.toNumber());
+    }
+
+    it("Test refund of a timelock in seconds", async () => {
+      const initiationTime = await clusterTime();
+      const secondsTimelock = new BN(3);
+      const secondsSwap = await aliceInitiateSwap({
+        timelock: secondsTimelock,
+        timelockKind: { seconds: {} },
+      });
+
+      // The expiry is relative to the unix timestamp of initiation
+      const { expiry } = await program.account.swapAccount.fetch(secondsSwap);
+      expect(expiry.sub(secondsTimelock).toNumber()).to.be.within(
+        initiationTime,
+        initiationTime + 10
+      );
+
+      await aliceRefundAtExpiry(secondsSwap, expiry.toNumber());
+    });
+
+    it("Test refund of a timestamp timelock", async () => {
+      const timestamp = new BN((await clusterTime()) + 3);
+      const timestampSwap = await aliceInitiateSwap({
+        timelock: timestamp,
+        timelockKind: { timestamp: {} },
+      });
+
+      // The expiry is the timestamp itself
+      const { expiry } = await program.account.swapAccount.fetch(timestampSwap);
+      expect(expiry.eq(timestamp)).to.be.true;
+
+      await aliceRefundAtExpiry(timestampSwap, expiry.toNumber());
+    });
+
+    it("Test instant refund", async () => {
+      await aliceInitiate(); // Re-initiating for this test
+      const aliceBalanceBefore = (
+        await connection.getTokenAccountBalance(aliceTokenAccount)
+      ).value.uiAmount;
+      const signature = await program.methods
+        .instantRefund()
+        .accounts({
+          refundeeTokenAccount: aliceTokenAccount,
+          redeemer: bob.publicKey,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          swapData,
+          tokenVault,
+          tokenProgram,
+        })
+        .signers([bob])
+        .rpc();
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+      console.log(`\tInstant Refund:  ${signature}`);
+
+      const aliceBalance = (
+        await connection.getTokenAccountBalance(aliceTokenAccount)
+      ).value.uiAmount;
+      expect(aliceBalance - aliceBalanceBefore).to.equal(swapAmount.toNumber());
+    });
+
+    it("Test instant refund creating the token account", async () => {
+      await aliceInitiate(); // Re-initiating for this test
+      const carol = newKeyWithoutTokenAccount();
+      await program.methods
+        .updateRefundee(carol.key.publicKey)
+        .accounts({ swapData, refundee: alice.publicKey })
+        .signers([alice])
+        .rpc();
+
+      const signature = await program.methods
+        .instantRefundWithAta()
+        .accounts({
+          refundee: carol.key.publicKey,
+          redeemer: bob.publicKey,
+          relayer: provider.wallet.publicKey,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          swapData,
+          tokenVault,
+          tokenProgram,
+        })
+        .signers([bob])
+        .rpc();
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+      console.log(`\tInstant Refund Creating ATA:  ${signature}`);
+
+      const carolBalance = (
+        await connection.getTokenAccountBalance(carol.tokenAccount)
+      ).value.uiAmount;
+      expect(carolBalance).to.equal(swapAmount.toNumber());
+    });
+
+    // Bob's consent to an instant refund of the swap, bound to his next nonce
+    async function bobRefundIntent() {
+      const [signerNonce] = web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("nonce"), bob.publicKey.toBuffer()],
+        program.programId
+      );
+      const nonce = await program.account.signerNonce
+        .fetchNullable(signerNonce)
+        .then((account) => account?.nextNonce ?? new BN(0));
+      const message = Buffer.concat([
+        Buffer.from("solana-spl-swaps:refund-intent"),
+        program.programId.toBuffer(),
+        program.coder.types.encode("refundIntent", { swapData, nonce }),
+      ]);
+      return web3.Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: bob.secretKey,
+        message,
+      });
+    }
+
+    async function instantRefundWithSignature(
+      ed25519Instruction: web3.TransactionInstruction
+    ) {
+      const signature = await program.methods
+        .instantRefundWithSignature()
+        .accounts({
+          refundeeTokenAccount: aliceTokenAccount,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          swapData,
+          tokenVault,
+          relayer: provider.wallet.publicKey,
+          tokenProgram,
+        })
+        .preInstructions([ed25519Instruction])
+        .rpc();
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+      console.log(`\tInstant Refund With Signature:  ${signature}`);
+    }
+
+    it("Test instant refund with signature", async () => {
+      await aliceInitiate(); // Re-initiating for this test
+      const aliceBalanceBefore = (
+        await connection.getTokenAccountBalance(aliceTokenAccount)
+      ).value.uiAmount;
+
+      // Bob consents off-chain, without signing the transaction
+      const ed25519Instruction = await bobRefundIntent();
+      await instantRefundWithSignature(ed25519Instruction);
+
+      const aliceBalance = (
+        await connection.getTokenAccountBalance(aliceTokenAccount)
+      ).value.uiAmount;
+      expect(aliceBalance - aliceBalanceBefore).to.equal(swapAmount.toNumber());
+
+      // The intent can not be replayed against a re-initiation of the same swap,
+      // as its nonce has been consumed
+      await aliceInitiate();
+      try {
+        await instantRefundWithSignature(ed25519Instruction);
+        expect.fail("Replaying a refund intent should fail");
+      } catch (e) {
+        expect(e.error?.errorCode?.code).to.equal("InvalidSignature");
+      }
+      await instantRefundWithSignature(await bobRefundIntent());
+    });
+
+    it("Test initiate with signature", async () => {
+      const [identityPda] = web3.PublicKey.findProgramAddressSync(
+        [],
+        program.programId
+      );
+      await spl.approve(
+        connection,
+        rentSponsor,
+        aliceTokenAccount,
+        identityPda,
+        alice,
+        swapAmount.toNumber(),
+        [],
+        undefined,
+        tokenProgram
+      );
+      const aliceBalanceBefore = (
+        await connection.getTokenAccountBalance(aliceTokenAccount)
+      ).value.uiAmount;
+
+      const order = {
+        mint: mint.publicKey,
+        redeemer: bob.publicKey,
+        refundee: alice.publicKey,
+        secretHash: [...secretHash],
+        swapAmount,
+        timelock,
+        salt,
+        timelockKind: { slots: {} },
+        hashAlgorithm: { sha256: {} },
+        maxSecretLen: 32,
+        relayerTip: new BN(0),
+        nonce: new BN(0),
+        deadline: new BN(Math.floor(Date.now() / 1000) + 600),
+      };
+      const message = Buffer.concat([
+        Buffer.from("solana-spl-swaps:swap-order"),
+        program.programId.toBuffer(),
+        program.coder.types.encode("swapOrder", order),
+      ]);
+      const ed25519Instruction =
+        web3.Ed25519Program.createInstructionWithPrivateKey({
+          privateKey: alice.secretKey,
+          message,
+        });
+
+      // The rent sponsor relays the initiation without alice signing the transaction
+      const signature = await program.methods
+        .initiateWithSignature(order, null)
+        .accounts({
+          owner: alice.publicKey,
+          ownerTokenAccount: aliceTokenAccount,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          tokenProgram,
+        })
+        .preInstructions([ed25519Instruction])
+        .signers([rentSponsor])
+        .rpc();
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+      console.log(`\tInitiate With Signature: ${signature}`);
+
+      const aliceBalance = (
+        await connection.getTokenAccountBalance(aliceTokenAccount)
+      ).value.uiAmount;
+      expect(aliceBalanceBefore - aliceBalance).to.equal(swapAmount.toNumber());
+
+      // Redeem to close the swap
+      await program.methods
+        .redeem(secret)
+        .accounts({
+          redeemerTokenAccount: bobTokenAccount,
+          feeTokenAccount: null,
+          relayer: provider.wallet.publicKey,
+          relayerTokenAccount: null,
+          mint: mint.publicKey,
+          rentSponsor: rentSponsor.publicKey,
+          swapData,
+          tokenVault,
+          tokenProgram,
+        })
+        .rpc();
+    });
+
+    it("Test reconcile and sweep surplus", async () => {
+      const [vaultState] = web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_state"), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      // Tokens sent to the vault by mistake
+      const strayAmount = 3;
+      await spl.mintTo(
+        connection,
+        rentSponsor,
+        mint.publicKey,
+        tokenVault,
+        mintAuthority,
+        strayAmount,
+        [],
+        undefined,
+        tokenProgram
+      );
+
+      const { totalLocked } = await program.account.vaultState.fetch(
+        vaultState
+      );
+      const vaultBalance = Number(
+        (await connection.getTokenAccountBalance(tokenVault)).value.amount
+      );
+      const surplus = vaultBalance - totalLocked.toNumber();
+      expect(surplus).to.be.at.least(strayAmount);
+
+      const reconcileSignature = await program.methods
+        .reconcile()
+        .accounts({ vaultState, tokenVault })
+        .rpc();
+      console.log(`\tReconcile: \t${reconcileSignature}`);
+
+      const funderBalanceBefore = (
+        await connection.getTokenAccountBalance(funderTokenAccount)
+      ).value.uiAmount;
+      const signature = await program.methods
+        .sweepSurplus()
+        .accounts({
+          mint: mint.publicKey,
+          destinationTokenAccount: funderTokenAccount,
+          admin: provider.wallet.publicKey,
+          tokenProgram,
+        })
+        .rpc();
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+      console.log(`\tSweep Surplus: \t${signature}`);
+
+      const funderBalance = (
+        await connection.getTokenAccountBalance(funderTokenAccount)
+      ).value.uiAmount;
+      expect(funderBalance - funderBalanceBefore).to.equal(surplus);
+
+      try {
+        await program.methods
+          .sweepSurplus()
+          .accounts({
+            mint: mint.publicKey,
+            destinationTokenAccount: funderTokenAccount,
+            admin: provider.wallet.publicKey,
+            tokenProgram,
+          })
+          .rpc();
+        expect.fail("Sweeping without surplus should fail");
+      } catch (err) {
+        expect(err.error?.errorCode?.code).to.equal("NoSurplus");
+      }
+    });
+  });
+}
+
+describe("Testing swaps of a Token-2022 mint with a transfer fee", () => {
+  const tokenProgram = spl.TOKEN_2022_PROGRAM_ID;
+  const transferFeeBps = 1_000; // 10%
+  const swapAmount = new BN(100);
+  // The fee withheld upon depositing the swap amount into a token vault
+  const depositFee = 10;
   const timelock = new BN(2); // 2 slots = 800 ms
+  const salt = new BN(crypto.randomBytes(8), "le");
   const secret: Buffer = crypto.randomBytes(32);
   const secretHash: Buffer = crypto
     .createHash("sha256")
     .update(secret)
     .digest();
-  const destinationData = crypto.randomBytes(256); // can be null
-
-  const mint = web3.Keypair.fromSeed(new Uint8Array(32).fill(33));
-  const mintAuthority = web3.Keypair.fromSeed(new Uint8Array(32).fill(36));
 
+  const mint = new web3.Keypair();
+  const mintAuthority = new web3.Keypair();
   // Alice, the initiator
   const alice = new web3.Keypair();
   let aliceTokenAccount: web3.PublicKey;
-
   // Bob, the redeemer
   const bob = new web3.Keypair();
   let bobTokenAccount: web3.PublicKey;
-
   // Sponsors the PDA rent and transaction fees
   const rentSponsor = new web3.Keypair();
 
-  // Facilitates initiate on behalf
-  const funder = new web3.Keypair();
-  let funderTokenAccount: web3.PublicKey;
-
   const [swapData] = web3.PublicKey.findProgramAddressSync(
     [
       mint.publicKey.toBuffer(),
@@ -52,6 +1512,7 @@ describe("Testing one way swap between Alice and Bob", () => {
       secretHash,
       swapAmount.toArrayLike(Buffer, "le", 8),
       timelock.toArrayLike(Buffer, "le", 8),
+      salt.toArrayLike(Buffer, "le", 8),
     ],
     program.programId
   );
@@ -59,215 +1520,638 @@ describe("Testing one way swap between Alice and Bob", () => {
     [mint.publicKey.toBuffer()],
     program.programId
   );
+  const [isolatedVault] = web3.PublicKey.findProgramAddressSync(
+    [swapData.toBuffer()],
+    program.programId
+  );
 
   let latestBlockHash: web3.BlockhashWithExpiryBlockHeight;
 
   before(async () => {
     latestBlockHash = await connection.getLatestBlockhash();
-    // Fund rent sponsor with 1 SOL
     const signature = await connection.requestAirdrop(
       rentSponsor.publicKey,
       web3.LAMPORTS_PER_SOL
     );
     await connection.confirmTransaction({ signature, ...latestBlockHash });
 
-    // Create Mint and Associated Token Accounts
-    try {
-      await spl.createMint(
-        connection,
-        rentSponsor,
+    const mintLen = spl.getMintLen([spl.ExtensionType.TransferFeeConfig]);
+    const transaction = new web3.Transaction().add(
+      web3.SystemProgram.createAccount({
+        fromPubkey: rentSponsor.publicKey,
+        newAccountPubkey: mint.publicKey,
+        space: mintLen,
+        lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+        programId: tokenProgram,
+      }),
+      spl.createInitializeTransferFeeConfigInstruction(
+        mint.publicKey,
         mintAuthority.publicKey,
-        null,
+        mintAuthority.publicKey,
+        transferFeeBps,
+        BigInt(1_000_000),
+        tokenProgram
+      ),
+      spl.createInitializeMintInstruction(
+        mint.publicKey,
         0,
-        mint
-      );
-    } catch (_) {
-      console.log("Mint already exists");
-    }
+        mintAuthority.publicKey,
+        null,
+        tokenProgram
+      )
+    );
+    await web3.sendAndConfirmTransaction(connection, transaction, [
+      rentSponsor,
+      mint,
+    ]);
+
     aliceTokenAccount = await spl.createAssociatedTokenAccount(
       connection,
       rentSponsor,
       mint.publicKey,
-      alice.publicKey
+      alice.publicKey,
+      undefined,
+      tokenProgram
     );
     bobTokenAccount = await spl.createAssociatedTokenAccount(
       connection,
       rentSponsor,
       mint.publicKey,
-      bob.publicKey
+      bob.publicKey,
+      undefined,
+      tokenProgram
     );
-    funderTokenAccount = await spl.createAssociatedTokenAccount(
+    // Minting is not subject to the transfer fee
+    await spl.mintTo(
       connection,
       rentSponsor,
       mint.publicKey,
-      funder.publicKey
+      aliceTokenAccount,
+      mintAuthority,
+      swapAmount.toNumber() * 10,
+      [],
+      undefined,
+      tokenProgram
     );
+  });
 
-    // Fund alice's token acc with tokens
-    await spl.mintTo(
+  async function aliceInitiate(isolated: boolean) {
+    const method = isolated
+      ? program.methods.initiateIsolated
+      : program.methods.initiate;
+    const signature = await method(
+      bob.publicKey,
+      alice.publicKey,
+      [...secretHash],
+      swapAmount,
+      timelock,
+      salt,
+      { slots: {} },
+      { sha256: {} },
+      32,
+      new BN(0),
+      null
+    )
+      .accounts({
+        funder: alice.publicKey,
+        funderTokenAccount: aliceTokenAccount,
+        mint: mint.publicKey,
+        rentSponsor: rentSponsor.publicKey,
+        tokenProgram,
+      })
+      .signers([alice, rentSponsor])
+      .rpc();
+    await connection.confirmTransaction({ signature, ...latestBlockHash });
+    console.log(`\tInitiate: \t${signature}`);
+  }
+
+  async function bobRedeem(vault: web3.PublicKey) {
+    const signature = await program.methods
+      .redeem(secret)
+      .accounts({
+        redeemerTokenAccount: bobTokenAccount,
+        feeTokenAccount: null,
+        relayer: provider.wallet.publicKey,
+        relayerTokenAccount: null,
+        mint: mint.publicKey,
+        rentSponsor: rentSponsor.publicKey,
+        swapData,
+        tokenVault: vault,
+        tokenProgram,
+      })
+      .rpc();
+    await connection.confirmTransaction({ signature, ...latestBlockHash });
+    console.log(`\tRedeem: \t${signature}`);
+  }
+
+  it("Test escrow of the amount received net of the transfer fee", async () => {
+    await aliceInitiate(false);
+    const { swapAmount: escrowedSwapAmount, escrowedAmount } =
+      await program.account.swapAccount.fetch(swapData);
+    expect(escrowedSwapAmount.toNumber()).to.equal(swapAmount.toNumber());
+    expect(escrowedAmount.toNumber()).to.equal(
+      swapAmount.toNumber() - depositFee
+    );
+
+    const bobBalanceBefore = Number(
+      (await connection.getTokenAccountBalance(bobTokenAccount)).value.amount
+    );
+    await bobRedeem(tokenVault);
+
+    // The escrowed amount is paid out, itself subject to the transfer fee
+    const payoutFee = Math.ceil(
+      (escrowedAmount.toNumber() * transferFeeBps) / 10_000
+    );
+    const bobBalance = Number(
+      (await connection.getTokenAccountBalance(bobTokenAccount)).value.amount
+    );
+    expect(bobBalance - bobBalanceBefore).to.equal(
+      escrowedAmount.toNumber() - payoutFee
+    );
+  });
+
+  it("Test harvest of withheld fees from an isolated vault", async () => {
+    await aliceInitiate(true);
+    const vaultAccount = await spl.getAccount(
+      connection,
+      isolatedVault,
+      undefined,
+      tokenProgram
+    );
+    expect(
+      Number(spl.getTransferFeeAmount(vaultAccount).withheldAmount)
+    ).to.equal(depositFee);
+    const mintWithheldBefore = spl.getTransferFeeConfig(
+      await spl.getMint(connection, mint.publicKey, undefined, tokenProgram)
+    ).withheldAmount;
+
+    await bobRedeem(isolatedVault);
+
+    // The fee withheld in the vault has been harvested to the mint for the vault to be closed
+    expect(await connection.getAccountInfo(isolatedVault)).to.be.null;
+    const mintWithheld = spl.getTransferFeeConfig(
+      await spl.getMint(connection, mint.publicKey, undefined, tokenProgram)
+    ).withheldAmount;
+    expect(Number(mintWithheld) - Number(mintWithheldBefore)).to.equal(
+      depositFee
+    );
+  });
+});
+
+describe("Testing the mint restrictions of the configuration", () => {
+  const swapAmount = new BN(10);
+  const timelock = new BN(2); // 2 slots = 800 ms
+  const secretHash: Buffer = crypto.randomBytes(32);
+
+  const mintAuthority = new web3.Keypair();
+  // A mint of the Token program with a freeze authority
+  const freezableMint = new web3.Keypair();
+  // A mint of the Token-2022 program with a permanent delegate
+  const delegatedMint = new web3.Keypair();
+  // Alice, the initiator
+  const alice = new web3.Keypair();
+  // Sponsors the PDA rent and transaction fees
+  const rentSponsor = new web3.Keypair();
+
+  before(async () => {
+    const latestBlockHash = await connection.getLatestBlockhash();
+    const signature = await connection.requestAirdrop(
+      rentSponsor.publicKey,
+      web3.LAMPORTS_PER_SOL
+    );
+    await connection.confirmTransaction({ signature, ...latestBlockHash });
+
+    await spl.createMint(
       connection,
       rentSponsor,
-      mint.publicKey,
-      aliceTokenAccount,
-      mintAuthority,
-      swapAmount.toNumber() * 10
+      mintAuthority.publicKey,
+      mintAuthority.publicKey,
+      0,
+      freezableMint,
+      undefined,
+      spl.TOKEN_PROGRAM_ID
+    );
+
+    const mintLen = spl.getMintLen([spl.ExtensionType.PermanentDelegate]);
+    const transaction = new web3.Transaction().add(
+      web3.SystemProgram.createAccount({
+        fromPubkey: rentSponsor.publicKey,
+        newAccountPubkey: delegatedMint.publicKey,
+        space: mintLen,
+        lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+        programId: spl.TOKEN_2022_PROGRAM_ID,
+      }),
+      spl.createInitializePermanentDelegateInstruction(
+        delegatedMint.publicKey,
+        mintAuthority.publicKey,
+        spl.TOKEN_2022_PROGRAM_ID
+      ),
+      spl.createInitializeMintInstruction(
+        delegatedMint.publicKey,
+        0,
+        mintAuthority.publicKey,
+        null,
+        spl.TOKEN_2022_PROGRAM_ID
+      )
+    );
+    await web3.sendAndConfirmTransaction(connection, transaction, [
+      rentSponsor,
+      delegatedMint,
+    ]);
+  });
+
+  async function expectInitiateToFail(
+    code: string,
+    mint: web3.PublicKey,
+    tokenProgram: web3.PublicKey
+  ) {
+    // The restrictions are enforced before any transfer, hence alice needs no tokens
+    const aliceTokenAccount = await spl.getOrCreateAssociatedTokenAccount(
+      connection,
+      rentSponsor,
+      mint,
+      alice.publicKey,
+      undefined,
+      undefined,
+      undefined,
+      tokenProgram
+    );
+    try {
+      await program.methods
+        .initiate(
+          new web3.Keypair().publicKey,
+          alice.publicKey,
+          [...secretHash],
+          swapAmount,
+          timelock,
+          new BN(0),
+          { slots: {} },
+          { sha256: {} },
+          32,
+          new BN(0),
+          null
+        )
+        .accounts({
+          funder: alice.publicKey,
+          funderTokenAccount: aliceTokenAccount.address,
+          mint,
+          rentSponsor: rentSponsor.publicKey,
+          tokenProgram,
+        })
+        .signers([alice, rentSponsor])
+        .rpc();
+      expect.fail(`Initiate should fail with ${code}`);
+    } catch (e) {
+      expect(e.error?.errorCode?.code).to.equal(code);
+    }
+  }
+
+  it("Test initiate of a mint with a freeze authority", async () => {
+    await setMintPolicy(freezableMint.publicKey, {
+      rejectFreezeAuthority: true,
+    });
+    await expectInitiateToFail(
+      "FreezeAuthorityNotAllowed",
+      freezableMint.publicKey,
+      spl.TOKEN_PROGRAM_ID
+    );
+  });
+
+  it("Test initiate of a mint with a permanent delegate", async () => {
+    await setMintPolicy(delegatedMint.publicKey, {
+      rejectPermanentDelegate: true,
+    });
+    await expectInitiateToFail(
+      "PermanentDelegateNotAllowed",
+      delegatedMint.publicKey,
+      spl.TOKEN_2022_PROGRAM_ID
+    );
+  });
+
+  it("Test initiate of a mint not on the allowlist", async () => {
+    // Neither restriction applies to the mint, which is however not allowed
+    await setMintPolicy(delegatedMint.publicKey, { allowed: false });
+    await updateConfig({ allowlistEnabled: true });
+    try {
+      await expectInitiateToFail(
+        "MintNotAllowed",
+        delegatedMint.publicKey,
+        spl.TOKEN_2022_PROGRAM_ID
+      );
+    } finally {
+      await updateConfig({ allowlistEnabled: false });
+    }
+  });
+});
+
+describe("Testing the protocol fee of a mint with a freeze authority", () => {
+  const swapAmount = new BN(10);
+  const timelock = new BN(2); // 2 slots = 800 ms
+  const salt = new BN(crypto.randomBytes(8), "le");
+  const secret: Buffer = crypto.randomBytes(32);
+  const secretHash: Buffer = crypto
+    .createHash("sha256")
+    .update(secret)
+    .digest();
+
+  const mint = new web3.Keypair();
+  // Also the freeze authority of the mint
+  const mintAuthority = new web3.Keypair();
+  // Alice, the initiator
+  const alice = new web3.Keypair();
+  let aliceTokenAccount: web3.PublicKey;
+  // Bob, the redeemer
+  const bob = new web3.Keypair();
+  let bobTokenAccount: web3.PublicKey;
+  let feeTokenAccount: web3.PublicKey;
+  // Sponsors the PDA rent and transaction fees
+  const rentSponsor = new web3.Keypair();
+
+  const [swapData] = web3.PublicKey.findProgramAddressSync(
+    [
+      mint.publicKey.toBuffer(),
+      bob.publicKey.toBuffer(),
+      alice.publicKey.toBuffer(),
+      secretHash,
+      swapAmount.toArrayLike(Buffer, "le", 8),
+      timelock.toArrayLike(Buffer, "le", 8),
+      salt.toArrayLike(Buffer, "le", 8),
+    ],
+    program.programId
+  );
+  const [tokenVault] = web3.PublicKey.findProgramAddressSync(
+    [mint.publicKey.toBuffer()],
+    program.programId
+  );
+
+  before(async () => {
+    const latestBlockHash = await connection.getLatestBlockhash();
+    const signature = await connection.requestAirdrop(
+      rentSponsor.publicKey,
+      web3.LAMPORTS_PER_SOL
     );
     await connection.confirmTransaction({ signature, ...latestBlockHash });
 
-    // Fund funder's token acc with tokens
+    await spl.createMint(
+      connection,
+      rentSponsor,
+      mintAuthority.publicKey,
+      mintAuthority.publicKey,
+      0,
+      mint
+    );
+    aliceTokenAccount = await spl.createAssociatedTokenAccount(
+      connection,
+      rentSponsor,
+      mint.publicKey,
+      alice.publicKey
+    );
+    bobTokenAccount = await spl.createAssociatedTokenAccount(
+      connection,
+      rentSponsor,
+      mint.publicKey,
+      bob.publicKey
+    );
+    feeTokenAccount = await spl.createAssociatedTokenAccount(
+      connection,
+      rentSponsor,
+      mint.publicKey,
+      feeRecipient
+    );
     await spl.mintTo(
       connection,
       rentSponsor,
       mint.publicKey,
-      funderTokenAccount,
+      aliceTokenAccount,
       mintAuthority,
-      swapAmount.toNumber() * 10
-    );
-    await connection.confirmTransaction({ signature, ...latestBlockHash });
-
-    console.log(
-      `Account Information:
-Alice     : ${alice.publicKey}\tAlice TokenAcc:\t${aliceTokenAccount}
-Bob       : ${bob.publicKey}\tBob TokenAcc:\t${bobTokenAccount}
-Swap Data : ${swapData}\tToken Vault:\t${tokenVault}
-Mint      : ${mint.publicKey}\tMint Authority:\t${mintAuthority.publicKey}
-Sponsor   : ${rentSponsor.publicKey}\n`
+      swapAmount.toNumber()
     );
   });
 
-  async function aliceInitiate() {
-    const signature = await program.methods
+  it("Test redeem waiving the fee of a frozen fee account", async () => {
+    // A fee of 10% for this mint
+    await setMintPolicy(mint.publicKey, { feeBps: 1_000 });
+    await program.methods
       .initiate(
         bob.publicKey,
         alice.publicKey,
         [...secretHash],
         swapAmount,
         timelock,
-        destinationData
+        salt,
+        { slots: {} },
+        { sha256: {} },
+        32,
+        new BN(0),
+        null
       )
       .accounts({
         funder: alice.publicKey,
         funderTokenAccount: aliceTokenAccount,
         mint: mint.publicKey,
         rentSponsor: rentSponsor.publicKey,
+        tokenProgram: spl.TOKEN_PROGRAM_ID,
       })
       .signers([alice, rentSponsor])
       .rpc();
-    await connection.confirmTransaction({ signature, ...latestBlockHash });
-    console.log(`\tInitiate: \t${signature}`);
-  }
+    await setMintPolicy(mint.publicKey);
+
+    // The fee account is frozen after the initiation, which must not prevent the redeem
+    await spl.freezeAccount(
+      connection,
+      rentSponsor,
+      feeTokenAccount,
+      mint.publicKey,
+      mintAuthority
+    );
+    const signature = await program.methods
+      .redeem(secret)
+      .accounts({
+        redeemerTokenAccount: bobTokenAccount,
+        feeTokenAccount,
+        relayer: provider.wallet.publicKey,
+        relayerTokenAccount: null,
+        mint: mint.publicKey,
+        rentSponsor: rentSponsor.publicKey,
+        swapData,
+        tokenVault,
+        tokenProgram: spl.TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    console.log(`\tRedeem Waiving Fee: \t${signature}`);
 
-  it("Test initiate on behalf", async () => {
-    const aliceBalanceBefore = (
-      await connection.getTokenAccountBalance(aliceTokenAccount)
+    const [event] = await fetchEvents(signature);
+    expect(event.name).to.equal("redeemed");
+    expect(event.data.feeAmount.toNumber()).to.equal(0);
+    const bobBalance = (
+      await connection.getTokenAccountBalance(bobTokenAccount)
     ).value.uiAmount;
-    const funderPreBalance = (
-      await connection.getTokenAccountBalance(funderTokenAccount)
+    expect(bobBalance).to.equal(swapAmount.toNumber());
+    const feeBalance = (
+      await connection.getTokenAccountBalance(feeTokenAccount)
     ).value.uiAmount;
+    expect(feeBalance).to.equal(0);
+  });
+});
+
+describe("Testing one way native SOL swap between Alice and Bob", () => {
+  const swapAmount = new BN(web3.LAMPORTS_PER_SOL / 10);
+  const timelock = new BN(2); // 2 slots = 800 ms
+  const salt = new BN(crypto.randomBytes(8), "le");
+  const secret: Buffer = crypto.randomBytes(32);
+  const secretHash: Buffer = crypto
+    .createHash("sha256")
+    .update(secret)
+    .digest();
+
+  // Alice, the initiator
+  const alice = new web3.Keypair();
+  // Bob, the redeemer
+  const bob = new web3.Keypair();
+  // Sponsors the PDA rent and transaction fees
+  const rentSponsor = new web3.Keypair();
+
+  const [swapData] = web3.PublicKey.findProgramAddressSync(
+    [
+      web3.PublicKey.default.toBuffer(),
+      bob.publicKey.toBuffer(),
+      alice.publicKey.toBuffer(),
+      secretHash,
+      swapAmount.toArrayLike(Buffer, "le", 8),
+      timelock.toArrayLike(Buffer, "le", 8),
+      salt.toArrayLike(Buffer, "le", 8),
+    ],
+    program.programId
+  );
+
+  let latestBlockHash: web3.BlockhashWithExpiryBlockHeight;
+
+  before(async () => {
+    latestBlockHash = await connection.getLatestBlockhash();
+    for (const account of [rentSponsor, alice, bob]) {
+      const signature = await connection.requestAirdrop(
+        account.publicKey,
+        web3.LAMPORTS_PER_SOL
+      );
+      await connection.confirmTransaction({ signature, ...latestBlockHash });
+    }
+  });
 
+  async function aliceInitiate() {
     const signature = await program.methods
-      .initiate(
+      .initiateNative(
         bob.publicKey,
         alice.publicKey,
         [...secretHash],
         swapAmount,
         timelock,
-        destinationData
+        salt,
+        { slots: {} },
+        { sha256: {} },
+        32,
+        new BN(0),
+        null
       )
       .accounts({
-        funder: funder.publicKey,
-        funderTokenAccount,
-        mint: mint.publicKey,
+        funder: alice.publicKey,
         rentSponsor: rentSponsor.publicKey,
       })
-      .signers([funder, rentSponsor])
+      .signers([alice, rentSponsor])
       .rpc();
-    console.log(`\tFunder initiated on behalf of alice: \t${signature}`);
+    await connection.confirmTransaction({ signature, ...latestBlockHash });
+    console.log(`\tInitiate: \t${signature}`);
+  }
 
-    const aliceBalance = (
-      await connection.getTokenAccountBalance(aliceTokenAccount)
-    ).value.uiAmount;
-    expect(aliceBalance).to.equal(aliceBalanceBefore);
+  it("Test redeem", async () => {
+    await aliceInitiate();
+    const bobBalanceBefore = await connection.getBalance(bob.publicKey);
+    const signature = await program.methods
+      .redeemNative(secret)
+      .accounts({
+        redeemer: bob.publicKey,
+        feeRecipient,
+        relayer: provider.wallet.publicKey,
+        rentSponsor: rentSponsor.publicKey,
+        swapData,
+      })
+      .rpc();
+    await connection.confirmTransaction({ signature, ...latestBlockHash });
+    console.log(`\tRedeem: \t${signature}`);
 
-    const funderPostBalance = (
-      await connection.getTokenAccountBalance(funderTokenAccount)
-    ).value.uiAmount;
-    expect(funderPostBalance).to.equal(
-      funderPreBalance - swapAmount.toNumber()
-    );
+    const bobBalance = await connection.getBalance(bob.publicKey);
+    expect(bobBalance - bobBalanceBefore).to.equal(swapAmount.toNumber());
   });
 
-  it("Test redeem", async () => {
-    // The previous testcase has initiated the swap
-    const bobBalanceBefore = (
-      await connection.getTokenAccountBalance(bobTokenAccount)
-    ).value.uiAmount;
+  it("Test redeem waiving a fee below the rent-exempt minimum", async () => {
+    // A fee of 0.01% is below the rent-exempt minimum of the new fee recipient
+    const newFeeRecipient = new web3.Keypair();
+    await setMintPolicy(web3.PublicKey.default, { feeBps: 1 });
+    await updateConfig({ feeRecipient: newFeeRecipient.publicKey });
+    try {
+      await aliceInitiate();
+    } finally {
+      await updateConfig({ feeRecipient });
+      await setMintPolicy(web3.PublicKey.default);
+    }
+
+    const bobBalanceBefore = await connection.getBalance(bob.publicKey);
     const signature = await program.methods
-      .redeem([...secret])
+      .redeemNative(secret)
       .accounts({
-        redeemerTokenAccount: bobTokenAccount,
+        redeemer: bob.publicKey,
+        feeRecipient: newFeeRecipient.publicKey,
+        relayer: provider.wallet.publicKey,
         rentSponsor: rentSponsor.publicKey,
         swapData,
-        tokenVault,
       })
       .rpc();
     await connection.confirmTransaction({ signature, ...latestBlockHash });
-    console.log(`\tRedeem: \t${signature}`);
+    console.log(`\tRedeem Waiving Fee: \t${signature}`);
 
-    const bobBalance = (
-      await connection.getTokenAccountBalance(bobTokenAccount)
-    ).value.uiAmount;
+    const bobBalance = await connection.getBalance(bob.publicKey);
     expect(bobBalance - bobBalanceBefore).to.equal(swapAmount.toNumber());
+    expect(await connection.getBalance(newFeeRecipient.publicKey)).to.equal(0);
   });
 
   it("Test refund", async () => {
-    await aliceInitiate(); // Re-initiating for this test
+    await aliceInitiate();
     const timelockMs = timelock.toNumber() * 400;
     console.log(`Awaiting timelock of ${timelockMs}ms for Refund`);
     await new Promise((r) => setTimeout(r, timelockMs + 1000)); // Add an extra sec
-    const aliceBalanceBefore = (
-      await connection.getTokenAccountBalance(aliceTokenAccount)
-    ).value.uiAmount;
+    const aliceBalanceBefore = await connection.getBalance(alice.publicKey);
     const signature = await program.methods
-      .refund()
+      .refundNative()
       .accounts({
-        refundeeTokenAccount: aliceTokenAccount,
+        refundee: alice.publicKey,
+        relayer: provider.wallet.publicKey,
         rentSponsor: rentSponsor.publicKey,
         swapData,
-        tokenVault,
       })
       .rpc();
     await connection.confirmTransaction({ signature, ...latestBlockHash });
     console.log(`\tRefund: \t${signature}`);
 
-    const aliceBalance = (
-      await connection.getTokenAccountBalance(aliceTokenAccount)
-    ).value.uiAmount;
+    const aliceBalance = await connection.getBalance(alice.publicKey);
     expect(aliceBalance - aliceBalanceBefore).to.equal(swapAmount.toNumber());
   });
 
   it("Test instant refund", async () => {
-    await aliceInitiate(); // Re-initiating for this test
-    const aliceBalanceBefore = (
-      await connection.getTokenAccountBalance(aliceTokenAccount)
-    ).value.uiAmount;
+    await aliceInitiate();
+    const aliceBalanceBefore = await connection.getBalance(alice.publicKey);
     const signature = await program.methods
-      .instantRefund()
+      .instantRefundNative()
       .accounts({
-        refundeeTokenAccount: aliceTokenAccount,
+        refundee: alice.publicKey,
         redeemer: bob.publicKey,
         rentSponsor: rentSponsor.publicKey,
         swapData,
-        tokenVault,
       })
       .signers([bob])
       .rpc();
     await connection.confirmTransaction({ signature, ...latestBlockHash });
     console.log(`\tInstant Refund:  ${signature}`);
 
-    const aliceBalance = (
-      await connection.getTokenAccountBalance(aliceTokenAccount)
-    ).value.uiAmount;
+    const aliceBalance = await connection.getBalance(alice.publicKey);
     expect(aliceBalance - aliceBalanceBefore).to.equal(swapAmount.toNumber());
   });
 });
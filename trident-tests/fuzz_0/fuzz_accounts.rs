@@ -19,10 +19,15 @@ pub struct FuzzAccounts {
 
     pub refundee_token_account: AccountsStorage,
 
+    /// Swap PDAs, derived from the swap parameters including the caller-supplied salt,
+    /// such that swaps with otherwise identical parameters are stored apart
     pub swap_data: AccountsStorage,
 
     pub token_vault: AccountsStorage,
 
+    /// Per-mint accounting PDAs of the shared token vaults
+    pub vault_state: AccountsStorage,
+
     pub rent_sponsor: AccountsStorage,
 
     pub mint: AccountsStorage,
@@ -30,4 +35,16 @@ pub struct FuzzAccounts {
     pub funder_token_account: AccountsStorage,
 
     pub identity_pda: AccountsStorage,
+
+    /// The singleton program configuration PDA
+    pub config: AccountsStorage,
+
+    /// Per-mint policy PDAs, which may not have been created
+    pub mint_policy: AccountsStorage,
+
+    pub fee_token_account: AccountsStorage,
+
+    pub relayer: AccountsStorage,
+
+    pub relayer_token_account: AccountsStorage,
 }
@@ -18,28 +18,55 @@ pub struct InitiateInstruction {
 pub struct InitiateInstructionAccounts {
     pub identity_pda: TridentAccount,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        storage::name = swap_data,
+        seeds = [
+            mint.as_ref(),
+            instruction_data.redeemer.get_pubkey().as_ref(),
+            instruction_data.refundee.get_pubkey().as_ref(),
+            &instruction_data.secret_hash,
+            &instruction_data.swap_amount.to_le_bytes(),
+            &instruction_data.timelock.to_le_bytes(),
+            &instruction_data.salt.to_le_bytes(),
+        ],
+    )]
     pub swap_data: TridentAccount,
 
-    #[account(mut)]
+    #[account(mut, storage::name = token_vault, seeds = [mint.as_ref()])]
     pub token_vault: TridentAccount,
 
+    #[account(mut, storage::name = vault_state, seeds = [b"vault_state", mint.as_ref()])]
+    pub vault_state: TridentAccount,
+
+    #[account(storage::name = config, seeds = [b"config"])]
+    pub config: TridentAccount,
+
+    #[account(storage::name = mint_policy, seeds = [b"mint_policy", mint.as_ref()])]
+    pub mint_policy: TridentAccount,
+
     #[account(signer)]
     pub funder: TridentAccount,
 
     #[account(mut)]
     pub funder_token_account: TridentAccount,
 
+    #[account(storage::name = mint)]
     pub mint: TridentAccount,
 
     #[account(mut, signer)]
     pub rent_sponsor: TridentAccount,
 
-    #[account(address = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA")]
     pub token_program: TridentAccount,
 
     #[account(address = "11111111111111111111111111111111")]
     pub system_program: TridentAccount,
+
+    #[account(address = "M1aPKkzFEg1Juvg686pMBsh7YrCTF7wPHwtEVGgE3XX")]
+    pub event_authority: TridentAccount,
+
+    #[account(address = "2WXpY8havGjfRxme9LUxtjFHTh1EfU3ur4v6wiK4KdNC")]
+    pub program: TridentAccount,
 }
 
 /// Instruction Data
@@ -55,6 +82,16 @@ pub struct InitiateInstructionData {
 
     pub timelock: u64,
 
+    pub salt: u64,
+
+    pub timelock_kind: TimelockKind,
+
+    pub hash_algorithm: HashAlgorithm,
+
+    pub max_secret_len: u16,
+
+    pub relayer_tip: u64,
+
     pub destination_data: Option<Vec<u8>>,
 }
 
@@ -18,12 +18,18 @@ pub struct InstantRefundInstruction {
 pub struct InstantRefundInstructionAccounts {
     pub identity_pda: TridentAccount,
 
-    #[account(mut)]
+    #[account(mut, storage::name = swap_data)]
     pub swap_data: TridentAccount,
 
+    #[account(mut)]
+    pub mint: TridentAccount,
+
     #[account(mut)]
     pub token_vault: TridentAccount,
 
+    #[account(mut, storage::name = vault_state)]
+    pub vault_state: TridentAccount,
+
     #[account(mut)]
     pub refundee_token_account: TridentAccount,
 
@@ -33,8 +39,13 @@ pub struct InstantRefundInstructionAccounts {
     #[account(mut)]
     pub rent_sponsor: TridentAccount,
 
-    #[account(address = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA")]
     pub token_program: TridentAccount,
+
+    #[account(address = "M1aPKkzFEg1Juvg686pMBsh7YrCTF7wPHwtEVGgE3XX")]
+    pub event_authority: TridentAccount,
+
+    #[account(address = "2WXpY8havGjfRxme9LUxtjFHTh1EfU3ur4v6wiK4KdNC")]
+    pub program: TridentAccount,
 }
 
 /// Instruction Data
@@ -18,20 +18,37 @@ pub struct RefundInstruction {
 pub struct RefundInstructionAccounts {
     pub identity_pda: TridentAccount,
 
-    #[account(mut)]
+    #[account(mut, storage::name = swap_data)]
     pub swap_data: TridentAccount,
 
+    #[account(mut)]
+    pub mint: TridentAccount,
+
     #[account(mut)]
     pub token_vault: TridentAccount,
 
+    #[account(mut, storage::name = vault_state)]
+    pub vault_state: TridentAccount,
+
     #[account(mut)]
     pub refundee_token_account: TridentAccount,
 
+    #[account(signer)]
+    pub relayer: TridentAccount,
+
+    #[account(mut)]
+    pub relayer_token_account: TridentAccount,
+
     #[account(mut)]
     pub rent_sponsor: TridentAccount,
 
-    #[account(address = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA")]
     pub token_program: TridentAccount,
+
+    #[account(address = "M1aPKkzFEg1Juvg686pMBsh7YrCTF7wPHwtEVGgE3XX")]
+    pub event_authority: TridentAccount,
+
+    #[account(address = "2WXpY8havGjfRxme9LUxtjFHTh1EfU3ur4v6wiK4KdNC")]
+    pub program: TridentAccount,
 }
 
 /// Instruction Data
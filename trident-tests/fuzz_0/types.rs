@@ -6,6 +6,47 @@ use trident_fuzz::fuzzing::*;
 ///
 /// You can define your own custom types here.
 
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone, Default)]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+
+    Keccak256,
+
+    Hash160,
+}
+
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone, Default)]
+pub enum TimelockKind {
+    #[default]
+    Slots,
+
+    Seconds,
+
+    Timestamp,
+}
+
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone, Default)]
+pub struct Config {
+    pub bump: u8,
+
+    pub admin: TridentPubkey,
+
+    pub guardian: TridentPubkey,
+
+    pub paused: bool,
+
+    pub fee_recipient: TridentPubkey,
+
+    pub fee_bps: u16,
+
+    pub allowlist_enabled: bool,
+
+    pub min_timelock: u64,
+
+    pub max_timelock: u64,
+}
+
 #[derive(Debug, BorshDeserialize, BorshSerialize, Clone, Default)]
 pub struct Initiated {
     pub mint: TridentPubkey,
@@ -16,10 +57,26 @@ pub struct Initiated {
 
     pub secret_hash: [u8; 32],
 
+    pub hash_algorithm: HashAlgorithm,
+
+    pub max_secret_len: u16,
+
     pub swap_amount: u64,
 
+    pub escrowed_amount: u64,
+
+    pub relayer_tip: u64,
+
     pub timelock: u64,
 
+    pub salt: u64,
+
+    pub order_id: [u8; 32],
+
+    pub timelock_kind: TimelockKind,
+
+    pub expiry: u64,
+
     pub destination_data: Option<Vec<u8>>,
 
     pub funder: TridentPubkey,
@@ -38,6 +95,50 @@ pub struct InstantRefunded {
     pub swap_amount: u64,
 
     pub timelock: u64,
+
+    pub salt: u64,
+
+    pub order_id: [u8; 32],
+}
+
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone, Default)]
+pub struct MintPolicy {
+    pub bump: u8,
+
+    pub mint: TridentPubkey,
+
+    pub paused: bool,
+
+    pub fee_bps: Option<u16>,
+
+    pub allowed: bool,
+
+    pub min_swap_amount: Option<u64>,
+
+    pub max_swap_amount: Option<u64>,
+
+    pub min_timelock: Option<u64>,
+
+    pub max_timelock: Option<u64>,
+
+    pub reject_freeze_authority: bool,
+
+    pub reject_permanent_delegate: bool,
+}
+
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone, Default)]
+pub struct Reconciled {
+    pub mint: TridentPubkey,
+
+    pub vault_balance: u64,
+
+    pub total_locked: u64,
+
+    pub open_swaps: u64,
+
+    pub surplus: u64,
+
+    pub deficit: u64,
 }
 
 #[derive(Debug, BorshDeserialize, BorshSerialize, Clone, Default)]
@@ -48,11 +149,37 @@ pub struct Redeemed {
 
     pub refundee: TridentPubkey,
 
-    pub secret: [u8; 32],
+    pub secret: Vec<u8>,
 
     pub swap_amount: u64,
 
+    pub fee_amount: u64,
+
+    pub relayer_tip: u64,
+
     pub timelock: u64,
+
+    pub salt: u64,
+
+    pub order_id: [u8; 32],
+}
+
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone, Default)]
+pub struct RedeemerTransferred {
+    pub order_id: [u8; 32],
+
+    pub previous_redeemer: TridentPubkey,
+
+    pub new_redeemer: TridentPubkey,
+}
+
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone, Default)]
+pub struct RefundeeUpdated {
+    pub order_id: [u8; 32],
+
+    pub previous_refundee: TridentPubkey,
+
+    pub new_refundee: TridentPubkey,
 }
 
 #[derive(Debug, BorshDeserialize, BorshSerialize, Clone, Default)]
@@ -67,28 +194,82 @@ pub struct Refunded {
 
     pub swap_amount: u64,
 
+    pub relayer_tip: u64,
+
     pub timelock: u64,
+
+    pub salt: u64,
+
+    pub order_id: [u8; 32],
+
+    pub expiry: u64,
+}
+
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone, Default)]
+pub struct SurplusSwept {
+    pub mint: TridentPubkey,
+
+    pub destination_token_account: TridentPubkey,
+
+    pub amount: u64,
 }
 
 #[derive(Debug, BorshDeserialize, BorshSerialize, Clone, Default)]
 pub struct SwapAccount {
     pub bump: u8,
 
-    pub expiry_slot: u64,
+    pub expiry: u64,
+
+    pub timelock_kind: TimelockKind,
 
     pub identity_pda_bump: u8,
 
+    pub token_vault: TridentPubkey,
+
+    pub vault_isolated: bool,
+
     pub rent_sponsor: TridentPubkey,
 
     pub mint: TridentPubkey,
 
     pub redeemer: TridentPubkey,
 
+    pub current_redeemer: TridentPubkey,
+
     pub refundee: TridentPubkey,
 
+    pub current_refundee: TridentPubkey,
+
     pub secret_hash: [u8; 32],
 
+    pub hash_algorithm: HashAlgorithm,
+
+    pub max_secret_len: u16,
+
     pub swap_amount: u64,
 
+    pub escrowed_amount: u64,
+
+    pub fee_bps: u16,
+
+    pub fee_recipient: TridentPubkey,
+
+    pub relayer_tip: u64,
+
     pub timelock: u64,
+
+    pub salt: u64,
+
+    pub order_id: [u8; 32],
+}
+
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone, Default)]
+pub struct VaultState {
+    pub bump: u8,
+
+    pub mint: TridentPubkey,
+
+    pub total_locked: u64,
+
+    pub open_swaps: u64,
 }